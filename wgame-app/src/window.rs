@@ -147,7 +147,7 @@ pub struct WaitRedraw<'a, 'b> {
 }
 
 impl<'a, 'b> Future for WaitRedraw<'a, 'b> {
-    type Output = Option<Redraw<'b>>;
+    type Output = Option<Redraw<'a>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let owner = &mut self.owner;
@@ -0,0 +1,55 @@
+#![forbid(unsafe_code)]
+
+use std::f32::consts::PI;
+
+use wgame::{
+    Library, Result, Runtime, Window, WindowConfig,
+    app::time::Instant,
+    create_windowed_task,
+    gfx::types::color,
+    glam::{Affine2, Vec2},
+    prelude::*,
+    texture::{Texture, TextureSettings},
+};
+
+/// Draws rotating `texture` until the window is closed.
+async fn draw(mut window: Window<'_>, gfx: &Library, texture: &Texture, speed: f32) -> Result<()> {
+    let quad = gfx
+        .shapes()
+        .rectangle((-Vec2::splat(0.5), Vec2::splat(0.5)))
+        .fill_texture(texture);
+    let start_time = Instant::now();
+    while let Some(mut frame) = window.next_frame().await? {
+        frame.clear(color::BLACK);
+        let angle = speed * (2.0 * PI) * (Instant::now() - start_time).as_secs_f32() / 10.0;
+        frame
+            .scene()
+            .add(&quad.transform(Affine2::from_angle(angle)));
+    }
+    Ok(())
+}
+
+#[wgame::window(title = "Main window", size = (800, 600), resizable = true, vsync = true)]
+async fn main(window: Window<'_>) -> Result<()> {
+    // Library and its textures are shared by both windows.
+    let gfx = Library::new(window.graphics());
+    let texture = gfx
+        .load_texture("assets/lenna.png", TextureSettings::linear())
+        .await?;
+
+    let second = create_windowed_task(
+        &Runtime::current(),
+        WindowConfig::default()
+            .title("Second window")
+            .size((400, 300))
+            .share_graphics(window.graphics()),
+        {
+            let (gfx, texture) = (gfx.clone(), texture.clone());
+            async move |window| draw(window, &gfx, &texture, -1.0).await
+        },
+    );
+
+    draw(window, &gfx, &texture, 1.0).await?;
+    second.terminate();
+    Ok(())
+}
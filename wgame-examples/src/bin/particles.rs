@@ -0,0 +1,156 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+
+use wgame::{
+    Library, Result, Window,
+    app::time::Instant,
+    gfx::{BlendMode, ComputePipeline, StorageBuffer, types::color},
+    glam::Vec2,
+    prelude::*,
+    shader::{Attribute, ShaderSource},
+    shapes::shader::InstanceData,
+};
+
+const COUNT: usize = 4096;
+const WORKGROUP_SIZE: usize = 64;
+
+/// Particle state that lives on the GPU only.
+#[derive(Clone, Copy, Attribute)]
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    /// Remaining lifetime in seconds.
+    life: f32,
+}
+
+/// Integrates particles and writes their instances, respawning expired ones at the emitter.
+const SHADER: &str = "
+{{ particle }}
+{{ instance }}
+@group(0) @binding(0) var<storage, read_write> particles: array<ParticlePacked>;
+@group(0) @binding(1) var<storage, read_write> instances: array<InstancePacked>;
+// Time step, random seed, texture coordinate transformation.
+@group(0) @binding(2) var<storage, read> params: array<f32>;
+
+fn random(seed: u32) -> f32 {
+    var x = seed * 747796405u + 2891336453u;
+    x = ((x >> ((x >> 28u) + 4u)) ^ x) * 277803737u;
+    return f32((x >> 22u) ^ x) / 4294967295.0;
+}
+
+@compute @workgroup_size({{ workgroup_size }})
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= arrayLength(&particles) {
+        return;
+    }
+    let dt = params[0];
+    var p = unpack_Particle(particles[i]);
+    p.life -= dt;
+    if p.life <= 0.0 {
+        let seed = i * 4u + u32(params[1]) * 16384u;
+        let angle = 1.5707963 + (random(seed) - 0.5) * 0.6;
+        let speed = 1.6 + 0.6 * random(seed + 1u);
+        p.pos = vec2(0.0, -1.0);
+        p.vel = speed * vec2(cos(angle), sin(angle));
+        p.life = 1.5 + random(seed + 2u);
+    }
+    p.vel.y -= 1.5 * dt;
+    p.pos += p.vel * dt;
+    particles[i] = pack_Particle(p);
+
+    let fade = clamp(p.life / 1.5, 0.0, 1.0);
+    let size = 0.02 + 0.02 * fade;
+    var instance: Instance;
+    instance.matrix_0 = vec4(size, 0.0, 0.0, 0.0);
+    instance.matrix_1 = vec4(0.0, size, 0.0, 0.0);
+    instance.matrix_2 = vec4(0.0, 0.0, 1.0, 0.0);
+    instance.matrix_3 = vec4(p.pos, 0.0, 1.0);
+    instance.tex_xform_m = vec4(params[2], params[3], params[4], params[5]);
+    instance.tex_xform_v = vec2(params[6], params[7]);
+    instance.tex_color = vec4(1.0, 0.3 + 0.6 * fade, 0.1 + 0.2 * fade, fade);
+    instances[i] = pack_Instance(instance);
+}
+";
+
+#[wgame::window(title = "GPU particles", size = (1200, 900), resizable = true, vsync = true)]
+async fn main(mut window: Window<'_>) -> Result<()> {
+    let gfx = Library::new(window.graphics());
+    let state = &gfx.state();
+
+    let pipeline = ComputePipeline::new(
+        state,
+        &ShaderSource::new("particles", SHADER)?,
+        &HashMap::from([
+            ("particle", StorageBuffer::<Particle>::wgsl("Particle")?),
+            ("instance", StorageBuffer::<InstanceData>::wgsl("Instance")?),
+            ("workgroup_size", WORKGROUP_SIZE.to_string()),
+        ]),
+        "main",
+    )?;
+
+    // Expired particles are respawned, so initial lifetimes spread the first spawns over a second.
+    let particles = StorageBuffer::from_items(
+        state,
+        &(0..COUNT)
+            .map(|i| Particle {
+                pos: Vec2::new(0.0, -10.0),
+                vel: Vec2::ZERO,
+                life: i as f32 / COUNT as f32,
+            })
+            .collect::<Vec<_>>(),
+    );
+    let instances = StorageBuffer::<InstanceData>::new(state, COUNT);
+    let params = StorageBuffer::<f32>::new(state, 8);
+    let bind_group = pipeline.bind_group(
+        0,
+        &[particles.buffer(), instances.buffer(), params.buffer()],
+    );
+
+    // Soft round spot, instance colors are multiplied by it.
+    let spot = gfx.texturing().gradient2([
+        [color::BLACK, color::BLACK, color::BLACK],
+        [color::BLACK, color::WHITE, color::BLACK],
+        [color::BLACK, color::BLACK, color::BLACK],
+    ]);
+    let coord_xform = spot.coord_xform();
+    let quad = gfx
+        .shapes()
+        .unit_quad()
+        .blend(BlendMode::Additive)
+        .fill_buffer(&spot, &instances);
+
+    let mut last_time = Instant::now();
+    let mut seed = 0;
+    while let Some(mut frame) = window.next_frame().await? {
+        let time = Instant::now();
+        let dt = (time - last_time).as_secs_f32().min(0.05);
+        last_time = time;
+        seed += 1;
+
+        let (m, v) = (coord_xform.matrix2, coord_xform.translation);
+        params.write(
+            0,
+            &[
+                dt,
+                (seed % 65536) as f32,
+                m.x_axis.x,
+                m.x_axis.y,
+                m.y_axis.x,
+                m.y_axis.y,
+                v.x,
+                v.y,
+            ],
+        );
+
+        frame.clear(color::BLACK);
+        frame.compute(
+            &pipeline,
+            &[&bind_group],
+            (COUNT.div_ceil(WORKGROUP_SIZE) as u32, 1, 1),
+        );
+        frame.scene().add(&quad);
+    }
+    Ok(())
+}
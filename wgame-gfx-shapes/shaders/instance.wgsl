@@ -1,5 +1,16 @@
 const PI: f32 = 3.141592653589793238462643;
 
+// Set for blend modes that expect premultiplied colors.
+override premultiply_alpha: bool = false;
+// Set for linear color space if the target format has no sRGB variant.
+override encode_srgb: bool = false;
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
 struct VertexData {
     @location(0) position: vec4<f32>,
     @location(1) local_coord: vec3<f32>,
@@ -37,6 +48,19 @@ var<uniform> view_matrix: mat4x4<f32>;
 @binding(1)
 var<uniform> view_color: vec4<f32>;
 
+{% if globals %}
+struct Globals {
+    {% for a in globals %}
+    {{ a.name }}: {{ a.ty }},
+    {% endfor %}
+};
+
+@group(1)
+@binding(0)
+var<uniform> globals: Globals;
+{% endif %}
+{% set texture_group = 2 if globals else 1 %}
+
 @vertex
 fn vertex_main(
     vertex: VertexData,
@@ -65,16 +89,16 @@ fn vertex_main(
     return output;
 }
 
-@group(1)
+@group({{ texture_group }})
 @binding(0)
 var texture: texture_2d<f32>;
 
-@group(1)
+@group({{ texture_group }})
 @binding(1)
 var sampler_: sampler;
 
 {% for (i, a) in fragment_uniforms|enumerate %}
-@group(2)
+@group({{ texture_group + 1 }})
 @binding({{ i }})
 var<uniform> {{ a.name }}: {{ a.ty }};
 {% endfor %}
@@ -90,5 +114,11 @@ fn fragment_main(input: VaryingData) -> @location(0) vec4<f32> {
 
     {{ fragment_color_source }}
 
+    if (encode_srgb) {
+        color = vec4<f32>(linear_to_srgb(color.rgb), color.a);
+    }
+    if (premultiply_alpha) {
+        color = vec4<f32>(color.rgb * color.a, color.a);
+    }
     return color;
 }
@@ -1,8 +1,10 @@
 use std::{f32::consts::PI, marker::PhantomData};
 
+use derivative::Derivative;
 use glam::{Affine2, Affine3A, Vec3};
 use wgame_gfx::{
-    Camera, Instance, Object, delegate_transformable, impl_object_for_instance, impl_transformable,
+    Bounds, Camera, Context, Graphics, Instance, Object, PipelineState, delegate_pipelined,
+    delegate_transformable, impl_object_for_instance, impl_pipelined, impl_transformable,
     prelude::Transformable, types::Transform,
 };
 use wgame_gfx_texture::Texture;
@@ -10,7 +12,7 @@ use wgame_shader::Attribute;
 
 use crate::{
     Mesh, Shape, ShapesLibrary, ShapesState, impl_textured,
-    pipeline::create_pipeline,
+    pipeline::ShapePipeline,
     render::{ShapeResource, ShapeStorage},
     shader::{InstanceData, ShaderConfig},
     shape::{ShapeFill, ShapeStroke},
@@ -29,18 +31,19 @@ struct RingVarying {
     tex_xform: Affine2,
 }
 
-#[derive(Clone)]
-pub struct CircleLibrary {
-    fill: wgpu::RenderPipeline,
-    stroke: wgpu::RenderPipeline,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CircleLibrary<C> {
+    fill: ShapePipeline<C>,
+    stroke: ShapePipeline<C>,
 }
 
-impl CircleLibrary {
+impl<C: Context> CircleLibrary<C> {
     pub fn new(state: &ShapesState) -> Self {
         Self {
-            fill: create_pipeline(
+            fill: ShapePipeline::new(
                 state,
-                &ShaderConfig {
+                ShaderConfig {
                     instance: CircleAttrs::bindings(),
                     varying: CircleAttrs::bindings(),
                     vertex_source: "
@@ -66,9 +69,9 @@ impl CircleLibrary {
             )
             .expect("Failed to create circle pipeline"),
 
-            stroke: create_pipeline(
+            stroke: ShapePipeline::new(
                 state,
-                &ShaderConfig {
+                ShaderConfig {
                     instance: CircleAttrs::bindings(),
                     varying: RingVarying::bindings(),
                     vertex_source: "
@@ -113,18 +116,20 @@ impl CircleLibrary {
 }
 
 #[must_use]
-#[derive(Clone)]
-pub struct Circle {
-    library: ShapesLibrary,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Circle<C = Camera> {
+    library: ShapesLibrary<C>,
     geometry: Mesh,
-    fill: wgpu::RenderPipeline,
-    stroke: wgpu::RenderPipeline,
+    fill: ShapePipeline<C>,
+    stroke: ShapePipeline<C>,
     inner_radius: f32,
     sector_angle: f32,
     xform: Affine3A,
+    pipeline_state: PipelineState,
 }
 
-impl Circle {
+impl<C> Circle<C> {
     pub fn inner_radius(&self, inner_radius: f32) -> Self {
         Self {
             inner_radius,
@@ -147,14 +152,15 @@ impl Circle {
     }
 }
 
-impl Shape for Circle {
-    fn library(&self) -> &ShapesLibrary {
+impl<C: Context> Shape for Circle<C> {
+    type Context = C;
+    fn library(&self) -> &ShapesLibrary<C> {
         &self.library
     }
 }
 
-impl ShapeFill for Circle {
-    type Fill = CircleFill;
+impl<C: Context> ShapeFill for Circle<C> {
+    type Fill = CircleFill<C>;
 
     fn fill_texture(&self, texture: &Texture) -> Self::Fill {
         CircleFill {
@@ -164,8 +170,8 @@ impl ShapeFill for Circle {
     }
 }
 
-impl ShapeStroke for Circle {
-    type Stroke = CircleStroke;
+impl<C: Context> ShapeStroke for Circle<C> {
+    type Stroke = CircleStroke<C>;
 
     fn stroke_texture(&self, line_width: f32, texture: &Texture) -> Self::Stroke {
         let half_width = line_width / 2.0;
@@ -178,16 +184,18 @@ impl ShapeStroke for Circle {
     }
 }
 
-impl_transformable!(Circle, xform);
+impl_transformable!(Circle<C>, xform);
+impl_pipelined!(Circle<C>, pipeline_state);
 
 #[must_use]
-#[derive(Clone)]
-pub struct CircleFill {
-    shape: Circle,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CircleFill<C = Camera> {
+    shape: Circle<C>,
     texture: Texture,
 }
 
-impl CircleFill {
+impl<C> CircleFill<C> {
     pub fn inner_radius(&self, inner_radius: f32) -> Self {
         Self {
             shape: self.shape.inner_radius(inner_radius),
@@ -203,10 +211,10 @@ impl CircleFill {
     }
 }
 
-impl Instance for CircleFill {
-    type Context = Camera;
-    type Resource = ShapeResource<CircleAttrs>;
-    type Storage = ShapeStorage<CircleAttrs>;
+impl<C: Context> Instance for CircleFill<C> {
+    type Context = C;
+    type Resource = ShapeResource<CircleAttrs, C>;
+    type Storage = ShapeStorage<CircleAttrs, C>;
 
     fn resource(&self) -> Self::Resource {
         ShapeResource {
@@ -214,7 +222,8 @@ impl Instance for CircleFill {
             texture: self.texture.resource(),
             uniforms: None,
             pipeline: self.shape.fill.clone(),
-            device: self.shape.library.state().device().clone(),
+            pipeline_state: self.shape.pipeline_state,
+            state: Graphics::clone(self.shape.library.state()),
             _ghost: PhantomData,
         }
     }
@@ -230,20 +239,29 @@ impl Instance for CircleFill {
             custom: self.shape.attribute(),
         });
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.shape
+            .geometry
+            .bounds()
+            .map(|bounds| bounds.transform(self.shape.xform))
+    }
 }
 
-impl_object_for_instance!(CircleFill);
-delegate_transformable!(CircleFill, shape);
-impl_textured!(CircleFill, texture);
+impl_object_for_instance!(CircleFill<C>);
+delegate_transformable!(CircleFill<C>, shape);
+delegate_pipelined!(CircleFill<C>, shape);
+impl_textured!(CircleFill<C>, texture);
 
 #[must_use]
-#[derive(Clone)]
-pub struct CircleStroke {
-    shape: Circle,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CircleStroke<C = Camera> {
+    shape: Circle<C>,
     texture: Texture,
 }
 
-impl CircleStroke {
+impl<C> CircleStroke<C> {
     pub fn sector(&self, angle: f32) -> Self {
         Self {
             shape: self.shape.sector(angle),
@@ -252,10 +270,10 @@ impl CircleStroke {
     }
 }
 
-impl Instance for CircleStroke {
-    type Context = Camera;
-    type Resource = ShapeResource<CircleAttrs>;
-    type Storage = ShapeStorage<CircleAttrs>;
+impl<C: Context> Instance for CircleStroke<C> {
+    type Context = C;
+    type Resource = ShapeResource<CircleAttrs, C>;
+    type Storage = ShapeStorage<CircleAttrs, C>;
 
     fn resource(&self) -> Self::Resource {
         ShapeResource {
@@ -263,7 +281,8 @@ impl Instance for CircleStroke {
             texture: self.texture.resource(),
             uniforms: None,
             pipeline: self.shape.stroke.clone(),
-            device: self.shape.library.state().device().clone(),
+            pipeline_state: self.shape.pipeline_state,
+            state: Graphics::clone(self.shape.library.state()),
             _ghost: PhantomData,
         }
     }
@@ -279,14 +298,22 @@ impl Instance for CircleStroke {
             custom: self.shape.attribute(),
         });
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.shape
+            .geometry
+            .bounds()
+            .map(|bounds| bounds.transform(self.shape.xform))
+    }
 }
 
-impl_object_for_instance!(CircleStroke);
-delegate_transformable!(CircleStroke, shape);
-impl_textured!(CircleStroke, texture);
+impl_object_for_instance!(CircleStroke<C>);
+delegate_transformable!(CircleStroke<C>, shape);
+delegate_pipelined!(CircleStroke<C>, shape);
+impl_textured!(CircleStroke<C>, texture);
 
-impl ShapesLibrary {
-    pub fn unit_circle(&self) -> Circle {
+impl<C: Context> ShapesLibrary<C> {
+    pub fn unit_circle(&self) -> Circle<C> {
         Circle {
             library: self.clone(),
             geometry: self.polygon.quad.clone(),
@@ -295,6 +322,7 @@ impl ShapesLibrary {
             inner_radius: 0.0,
             sector_angle: 2.0 * PI,
             xform: Affine3A::IDENTITY,
+            pipeline_state: PipelineState::default(),
         }
     }
 }
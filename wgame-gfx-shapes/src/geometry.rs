@@ -1,12 +1,17 @@
+use std::hash::{Hash, Hasher};
+
 use derive_more::Deref;
+use wgame_gfx::Bounds;
 use wgame_shader::{Attribute, BytesSink};
 use wgpu::util::DeviceExt;
 
 use crate::{ShapesState, shader::Vertex};
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug, Deref)]
+#[derive(Clone, Debug, Deref)]
 pub struct Vertices {
     count: u32,
+    /// Bounds of vertex positions, computed on creation.
+    bounds: Option<Bounds>,
     #[deref]
     buffer: wgpu::Buffer,
 }
@@ -23,6 +28,7 @@ impl Vertices {
     pub fn new(state: &ShapesState, vertices: &[Vertex]) -> Self {
         Self {
             count: vertices.len() as u32,
+            bounds: Bounds::from_points(vertices.iter().map(|v| v.pos.truncate() / v.pos.w)),
             buffer: state
                 .device()
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -42,11 +48,29 @@ impl Vertices {
     pub fn count(&self) -> u32 {
         self.count
     }
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
     pub fn buffer(&self) -> &wgpu::Buffer {
         &self.buffer
     }
 }
 
+impl PartialEq for Vertices {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count && self.buffer == other.buffer
+    }
+}
+
+impl Eq for Vertices {}
+
+impl Hash for Vertices {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.count.hash(state);
+        self.buffer.hash(state);
+    }
+}
+
 impl Indices {
     pub fn new(state: &ShapesState, indices: &[u32]) -> Self {
         Self {
@@ -100,6 +124,10 @@ impl Mesh {
             Some(indices) => indices.count(),
         }
     }
+    /// Bounds of the mesh in local coordinates.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.vertices.bounds()
+    }
     pub fn vertices(&self) -> &Vertices {
         &self.vertices
     }
@@ -13,8 +13,9 @@ pub mod shader;
 mod shape;
 
 use core::ops::Deref;
+use derivative::Derivative;
 use wgame_gfx::{
-    Graphics,
+    Camera, Context, Graphics,
     types::{Color, color},
 };
 use wgame_gfx_texture::{Texture, TexturingLibrary, TexturingState};
@@ -25,7 +26,8 @@ use crate::{circle::CircleLibrary, polygon::PolygonLibrary};
 pub use self::{
     circle::{Circle, CircleFill, CircleStroke},
     geometry::Mesh,
-    polygon::{Polygon, PolygonFill},
+    pipeline::ShapePipeline,
+    polygon::{Polygon, PolygonBuffer, PolygonFill},
     shape::{Shape, Textured},
 };
 
@@ -54,18 +56,28 @@ impl Deref for ShapesState {
     }
 }
 
-/// 2D graphics library for rendering shapes.
-#[derive(Clone)]
-pub struct ShapesLibrary {
+/// 2D graphics library for rendering shapes in context `C`.
+///
+/// Shape pipelines are built for [`globals`](Context::globals) of the context.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct ShapesLibrary<C = Camera> {
     state: ShapesState,
-    polygon: PolygonLibrary,
-    circle: CircleLibrary,
+    polygon: PolygonLibrary<C>,
+    circle: CircleLibrary<C>,
     white_texture: Texture,
 }
 
 impl ShapesLibrary {
     /// Creates a new shapes library.
     pub fn new(state: &Graphics, texture: &TexturingLibrary) -> Self {
+        Self::for_context(state, texture)
+    }
+}
+
+impl<C: Context> ShapesLibrary<C> {
+    /// Creates a new shapes library for another context, e.g. a [`Camera`] with globals.
+    pub fn for_context(state: &Graphics, texture: &TexturingLibrary) -> Self {
         assert_eq!(state, &**texture.state());
         let state = ShapesState {
             texture: texture.state().clone(),
@@ -80,7 +92,9 @@ impl ShapesLibrary {
             state,
         }
     }
+}
 
+impl<C> ShapesLibrary<C> {
     /// Returns a reference to the shapes state.
     pub fn state(&self) -> &ShapesState {
         &self.state
@@ -1,78 +1,126 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    rc::Rc,
+};
 
 use anyhow::Result;
-use wgame_shader::{Attribute, ShaderSource};
+use derivative::Derivative;
+use serde::Serialize;
+use wgame_gfx::{Camera, Context, PipelineKey, PipelineState, PipelineVariants, VertexBufferKey};
+use wgame_shader::{Attribute, BindingList, ShaderSource};
 
 use crate::{
     ShapesState,
     shader::{InstanceData, ShaderConfig, Vertex},
 };
 
-pub fn create_pipeline(state: &ShapesState, config: &ShaderConfig) -> Result<wgpu::RenderPipeline> {
-    let device = state.device();
-    let swapchain_format = state.format();
+/// Shape pipeline with lazily created variants for different pipeline states.
+///
+/// The pipeline is built for [`globals`](Context::globals) of the context `C`.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct ShapePipeline<C = Camera> {
+    state: ShapesState,
+    config: Rc<ShaderConfig>,
+    variants: Rc<PipelineVariants>,
+    _context: PhantomData<C>,
+}
+
+impl<C: Context> ShapePipeline<C> {
+    pub fn new(state: &ShapesState, config: ShaderConfig) -> Result<Self> {
+        let default = create_pipeline(state, &config, PipelineState::default(), C::globals())?;
+        let variants = PipelineVariants::default();
+        variants.get_or_create(PipelineState::default(), || default);
+        Ok(Self {
+            state: state.clone(),
+            config: Rc::new(config),
+            variants: Rc::new(variants),
+            _context: PhantomData,
+        })
+    }
+
+    /// Returns variant of the pipeline, creating it if needed.
+    pub fn get(&self, pipeline_state: PipelineState) -> wgpu::RenderPipeline {
+        self.variants.get_or_create(pipeline_state, || {
+            create_pipeline(&self.state, &self.config, pipeline_state, C::globals())
+                .expect("Failed to create shape pipeline variant")
+        })
+    }
+}
+
+impl<C> PartialEq for ShapePipeline<C> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.variants, &other.variants)
+    }
+}
 
-    let shader_source = wgpu::ShaderSource::Wgsl(Cow::Owned(
-        ShaderSource::new(
-            "shaders/instance.wgsl",
-            include_str!("../shaders/instance.wgsl"),
-        )?
-        .substitute(config)?,
-    ));
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("shader"),
-        source: shader_source,
-    });
+impl<C> Eq for ShapePipeline<C> {}
 
-    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: None,
-        bind_group_layouts: &[
-            state.camera_bind_group_layout(),
-            &state.texture().float_bind_group_layout,
-        ],
-        immediate_size: 0,
-    });
+impl<C> Hash for ShapePipeline<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.variants).hash(state);
+    }
+}
+
+impl<C> Debug for ShapePipeline<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShapePipeline")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Values substituted into the shader template.
+#[derive(Debug, Serialize)]
+struct Substitution<'a> {
+    #[serde(flatten)]
+    config: &'a ShaderConfig,
+    globals: Option<&'a BindingList>,
+}
+
+/// Gets pipeline from the cache of the graphics state, so identical configs share a single pipeline.
+pub fn create_pipeline(
+    state: &ShapesState,
+    config: &ShaderConfig,
+    pipeline_state: PipelineState,
+    globals: Option<BindingList>,
+) -> Result<wgpu::RenderPipeline> {
+    let source = ShaderSource::new(
+        "shaders/instance.wgsl",
+        include_str!("../shaders/instance.wgsl"),
+    )?
+    .substitute(&Substitution {
+        config,
+        globals: globals.as_ref(),
+    })?;
 
     let vertex_attributes = Vertex::bindings();
     let instance_attributes = InstanceData::<()>::bindings().chain(config.instance.clone());
-    let vertex_buffers = [
-        wgpu::VertexBufferLayout {
+    let vertex_buffers = vec![
+        VertexBufferKey {
             array_stride: vertex_attributes.size(),
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &vertex_attributes.layout(0)?,
+            attributes: vertex_attributes.layout(0)?,
         },
-        wgpu::VertexBufferLayout {
+        VertexBufferKey {
             array_stride: instance_attributes.size(),
             step_mode: wgpu::VertexStepMode::Instance,
-            attributes: &instance_attributes.layout(vertex_attributes.count())?,
+            attributes: instance_attributes.layout(vertex_attributes.count())?,
         },
     ];
 
-    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: None,
-        layout: Some(&pipeline_layout),
-        vertex: wgpu::VertexState {
-            module: &shader,
-            entry_point: Some("vertex_main"),
-            buffers: &vertex_buffers,
-            compilation_options: Default::default(),
-        },
-        fragment: Some(wgpu::FragmentState {
-            module: &shader,
-            entry_point: Some("fragment_main"),
-            compilation_options: Default::default(),
-            targets: &[Some(wgpu::ColorTargetState {
-                format: swapchain_format,
-                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                write_mask: wgpu::ColorWrites::ALL,
-            })],
-        }),
-        primitive: wgpu::PrimitiveState::default(),
-        depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
-        multiview_mask: None,
-        cache: None,
-    });
-
-    Ok(pipeline)
+    Ok(state.render_pipeline(&PipelineKey {
+        source: Cow::Owned(source),
+        bind_group_layouts: [state.camera_bind_group_layout().clone()]
+            .into_iter()
+            .chain(globals.map(|globals| state.globals_bind_group_layout(&globals)))
+            .chain([state.texture().float_bind_group_layout.clone()])
+            .collect(),
+        vertex_buffers,
+        state: pipeline_state,
+        target: None,
+    }))
 }
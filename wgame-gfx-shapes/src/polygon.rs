@@ -3,9 +3,12 @@ use std::{
     marker::PhantomData,
 };
 
+use derivative::Derivative;
 use glam::{Affine3A, Mat3, Vec2, Vec3, Vec4};
 use wgame_gfx::{
-    Camera, Instance, Object, delegate_transformable, impl_object_for_instance, impl_transformable,
+    Bounds, Camera, Context, Graphics, Instance, Object, PipelineState, StorageBuffer,
+    delegate_pipelined, delegate_transformable, impl_object_for_instance, impl_pipelined,
+    impl_transformable,
     modifiers::Transformable,
     types::{Position, Transform},
 };
@@ -13,21 +16,22 @@ use wgame_gfx_texture::Texture;
 
 use crate::{
     Mesh, Shape, ShapesLibrary, ShapesState, impl_textured,
-    pipeline::create_pipeline,
-    render::{ShapeResource, ShapeStorage},
+    pipeline::ShapePipeline,
+    render::{BufferResource, BufferStorage, ShapeResource, ShapeStorage},
     shader::{InstanceData, Vertex},
     shape::ShapeFill,
 };
 
-#[derive(Clone)]
-pub struct PolygonLibrary {
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct PolygonLibrary<C> {
     pub triangle: Mesh,
     pub quad: Mesh,
     pub hexagon: Mesh,
-    pub fill: wgpu::RenderPipeline,
+    pub fill: ShapePipeline<C>,
 }
 
-impl PolygonLibrary {
+impl<C: Context> PolygonLibrary<C> {
     pub fn new(state: &ShapesState) -> Self {
         let triangle = Mesh::from_arrays(
             state,
@@ -75,8 +79,8 @@ impl PolygonLibrary {
             Some(&[0, 1, 2, 2, 3, 4, 4, 5, 0, 0, 2, 4]),
         );
 
-        let pipeline =
-            create_pipeline(state, &Default::default()).expect("Failed to create polygon pipeline");
+        let pipeline = ShapePipeline::new(state, Default::default())
+            .expect("Failed to create polygon pipeline");
 
         Self {
             triangle,
@@ -88,22 +92,54 @@ impl PolygonLibrary {
 }
 
 #[must_use]
-#[derive(Clone)]
-pub struct Polygon {
-    library: ShapesLibrary,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Polygon<C = Camera> {
+    library: ShapesLibrary<C>,
     geometry: Mesh,
-    fill: wgpu::RenderPipeline,
+    fill: ShapePipeline<C>,
     xform: Affine3A,
+    pipeline_state: PipelineState,
 }
 
-impl Shape for Polygon {
-    fn library(&self) -> &ShapesLibrary {
+impl<C> Polygon<C> {
+    /// Draws the polygon using custom shader pipeline.
+    ///
+    /// The pipeline must not declare additional instance attributes.
+    pub fn with_pipeline(&self, pipeline: &ShapePipeline<C>) -> Self {
+        Self {
+            fill: pipeline.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Draws instances of the polygon stored in a buffer, e.g. written by a compute shader.
+    ///
+    /// Transformations, texture coordinate transformations and colors of instances are read from the buffer,
+    /// so transformation of the polygon is ignored. Texture coordinates are mapped into the atlas of `texture`,
+    /// so instances should contain [`Texture::coord_xform`] to cover the whole texture.
+    pub fn fill_buffer(
+        &self,
+        texture: &Texture,
+        instances: &StorageBuffer<InstanceData>,
+    ) -> PolygonBuffer<C> {
+        PolygonBuffer {
+            shape: self.clone(),
+            texture: texture.clone(),
+            instances: instances.clone(),
+        }
+    }
+}
+
+impl<C: Context> Shape for Polygon<C> {
+    type Context = C;
+    fn library(&self) -> &ShapesLibrary<C> {
         &self.library
     }
 }
 
-impl ShapeFill for Polygon {
-    type Fill = PolygonFill;
+impl<C: Context> ShapeFill for Polygon<C> {
+    type Fill = PolygonFill<C>;
 
     fn fill_texture(&self, texture: &Texture) -> Self::Fill {
         PolygonFill {
@@ -113,19 +149,21 @@ impl ShapeFill for Polygon {
     }
 }
 
-impl_transformable!(Polygon, xform);
+impl_transformable!(Polygon<C>, xform);
+impl_pipelined!(Polygon<C>, pipeline_state);
 
 #[must_use]
-#[derive(Clone)]
-pub struct PolygonFill {
-    shape: Polygon,
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct PolygonFill<C = Camera> {
+    shape: Polygon<C>,
     texture: Texture,
 }
 
-impl Instance for PolygonFill {
-    type Context = Camera;
-    type Resource = ShapeResource<()>;
-    type Storage = ShapeStorage<()>;
+impl<C: Context> Instance for PolygonFill<C> {
+    type Context = C;
+    type Resource = ShapeResource<(), C>;
+    type Storage = ShapeStorage<(), C>;
 
     fn resource(&self) -> Self::Resource {
         ShapeResource {
@@ -133,7 +171,8 @@ impl Instance for PolygonFill {
             texture: self.texture.resource(),
             uniforms: None,
             pipeline: self.shape.fill.clone(),
-            device: self.shape.library.state().device().clone(),
+            pipeline_state: self.shape.pipeline_state,
+            state: Graphics::clone(self.shape.library.state()),
             _ghost: PhantomData,
         }
     }
@@ -149,32 +188,82 @@ impl Instance for PolygonFill {
             custom: (),
         });
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.shape
+            .geometry
+            .bounds()
+            .map(|bounds| bounds.transform(self.shape.xform))
+    }
+}
+
+impl_object_for_instance!(PolygonFill<C>);
+delegate_transformable!(PolygonFill<C>, shape);
+delegate_pipelined!(PolygonFill<C>, shape);
+impl_textured!(PolygonFill<C>, texture);
+
+#[must_use]
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct PolygonBuffer<C = Camera> {
+    shape: Polygon<C>,
+    texture: Texture,
+    instances: StorageBuffer<InstanceData>,
+}
+
+impl<C: Context> Instance for PolygonBuffer<C> {
+    type Context = C;
+    type Resource = BufferResource<C>;
+    type Storage = BufferStorage<C>;
+
+    fn resource(&self) -> Self::Resource {
+        BufferResource {
+            shape: ShapeResource {
+                vertices: self.shape.geometry.clone(),
+                texture: self.texture.resource(),
+                uniforms: None,
+                pipeline: self.shape.fill.clone(),
+                pipeline_state: self.shape.pipeline_state,
+                state: Graphics::clone(self.shape.library.state()),
+                _ghost: PhantomData,
+            },
+            instances: self.instances.buffer().clone(),
+        }
+    }
+
+    fn new_storage(&self) -> Self::Storage {
+        BufferStorage::new(self.resource())
+    }
+
+    fn store(&self, storage: &mut Self::Storage) {
+        storage.store(&self.instances);
+    }
 }
 
-impl_object_for_instance!(PolygonFill);
-delegate_transformable!(PolygonFill, shape);
-impl_textured!(PolygonFill, texture);
+impl_object_for_instance!(PolygonBuffer<C>);
+delegate_pipelined!(PolygonBuffer<C>, shape);
 
-impl ShapesLibrary {
-    fn polygon(&self, mesh: Mesh) -> Polygon {
+impl<C: Context> ShapesLibrary<C> {
+    fn polygon(&self, mesh: Mesh) -> Polygon<C> {
         Polygon {
             library: self.clone(),
             geometry: mesh,
             fill: self.polygon.fill.clone(),
             xform: Affine3A::IDENTITY,
+            pipeline_state: PipelineState::default(),
         }
     }
 
-    pub fn triangle(&self, a: impl Position, b: impl Position, c: impl Position) -> Polygon {
+    pub fn triangle(&self, a: impl Position, b: impl Position, c: impl Position) -> Polygon<C> {
         self.polygon(self.polygon.triangle.clone())
             .transform(Mat3::from_cols(a.to_xyz(), b.to_xyz(), c.to_xyz()))
     }
 
-    pub fn unit_quad(&self) -> Polygon {
+    pub fn unit_quad(&self) -> Polygon<C> {
         self.polygon(self.polygon.quad.clone())
     }
 
-    pub fn rectangle(&self, (min, max): (Vec2, Vec2)) -> Polygon {
+    pub fn rectangle(&self, (min, max): (Vec2, Vec2)) -> Polygon<C> {
         let center = 0.5 * (min + max);
         let half_size = 0.5 * (max - min);
         let affine = Affine3A::from_mat3_translation(
@@ -184,12 +273,12 @@ impl ShapesLibrary {
         self.unit_quad().transform(affine)
     }
 
-    pub fn unit_hexagon(&self) -> Polygon {
+    pub fn unit_hexagon(&self) -> Polygon<C> {
         self.polygon(self.polygon.hexagon.clone())
     }
 }
 
-impl Debug for Polygon {
+impl<C> Debug for Polygon<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Polygon<{}>", self.geometry.count())
     }
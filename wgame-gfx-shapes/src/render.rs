@@ -2,12 +2,14 @@ use std::marker::PhantomData;
 
 use derivative::Derivative;
 use smallvec::SmallVec;
-use wgame_gfx::{Camera, Context, Renderer, Resource, Storage};
+use wgame_gfx::{
+    Context, Graphics, PipelineState, PooledBuffer, Profiler, Renderer, Resource, Storage,
+    StorageBuffer,
+};
 use wgame_gfx_texture::TextureResource;
 use wgame_shader::{Attribute, BytesSink};
-use wgpu::util::DeviceExt;
 
-use crate::{Mesh, shader::InstanceData};
+use crate::{Mesh, pipeline::ShapePipeline, shader::InstanceData};
 
 #[derive(Derivative)]
 #[derivative(
@@ -17,32 +19,68 @@ use crate::{Mesh, shader::InstanceData};
     Hash(bound = ""),
     Debug(bound = "")
 )]
-pub struct ShapeResource<T: Attribute> {
+pub struct ShapeResource<T: Attribute, C> {
     pub vertices: Mesh,
     pub texture: TextureResource,
     pub uniforms: Option<wgpu::BindGroup>,
-    pub pipeline: wgpu::RenderPipeline,
-    pub device: wgpu::Device,
+    pub pipeline: ShapePipeline<C>,
+    pub pipeline_state: PipelineState,
+    pub state: Graphics,
     pub _ghost: PhantomData<T>,
 }
 
-pub struct ShapeStorage<T: Attribute> {
-    resource: ShapeResource<T>,
+pub struct ShapeStorage<T: Attribute, C> {
+    resource: ShapeResource<T, C>,
     pub instances: Vec<InstanceData<T>>,
 }
 
-pub struct ShapeRenderer {
+/// Shape instances stored in a GPU buffer, e.g. written by a compute shader.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Debug(bound = "")
+)]
+pub struct BufferResource<C> {
+    pub shape: ShapeResource<(), C>,
+    pub instances: wgpu::Buffer,
+}
+
+pub struct BufferStorage<C> {
+    resource: BufferResource<C>,
+    instance_count: u32,
+}
+
+enum InstanceBuffer {
+    Pooled(PooledBuffer),
+    Storage(wgpu::Buffer),
+}
+
+impl InstanceBuffer {
+    fn slice(&self) -> wgpu::BufferSlice<'_> {
+        match self {
+            Self::Pooled(buffer) => buffer.slice(),
+            Self::Storage(buffer) => buffer.slice(..),
+        }
+    }
+}
+
+pub struct ShapeRenderer<C> {
     geometry: Mesh,
     instance_count: u32,
-    instance_buffer: wgpu::Buffer,
+    instance_buffer: InstanceBuffer,
     uniforms: SmallVec<[wgpu::BindGroup; 2]>,
-    pipeline: wgpu::RenderPipeline,
+    pipeline: ShapePipeline<C>,
+    pipeline_state: PipelineState,
+    profiler: Profiler,
 }
 
-impl<T: Attribute> Resource for ShapeResource<T> {}
+impl<T: Attribute, C: Context> Resource for ShapeResource<T, C> {}
 
-impl<T: Attribute> ShapeStorage<T> {
-    pub(crate) fn new(resource: ShapeResource<T>) -> Self {
+impl<T: Attribute, C> ShapeStorage<T, C> {
+    pub(crate) fn new(resource: ShapeResource<T, C>) -> Self {
         Self {
             resource,
             instances: Vec::new(),
@@ -50,10 +88,10 @@ impl<T: Attribute> ShapeStorage<T> {
     }
 }
 
-impl<T: Attribute> Storage for ShapeStorage<T> {
-    type Context = Camera;
-    type Resource = ShapeResource<T>;
-    type Renderer = ShapeRenderer;
+impl<T: Attribute, C: Context> Storage for ShapeStorage<T, C> {
+    type Context = C;
+    type Resource = ShapeResource<T, C>;
+    type Renderer = ShapeRenderer<C>;
 
     fn resource(&self) -> Self::Resource {
         self.resource.clone()
@@ -65,25 +103,62 @@ impl<T: Attribute> Storage for ShapeStorage<T> {
             instance.store(&mut buffer);
         }
         let buffer_data = buffer.into_data();
-        let instance_buffer =
-            self.resource
-                .device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("instances"),
-                    contents: &buffer_data,
-                    usage: wgpu::BufferUsages::VERTEX,
-                });
+        let instance_buffer = self
+            .resource
+            .state
+            .buffers()
+            .write(wgpu::BufferUsages::VERTEX, &buffer_data);
         ShapeRenderer {
             geometry: self.resource.vertices.clone(),
             instance_count,
-            instance_buffer,
+            instance_buffer: InstanceBuffer::Pooled(instance_buffer),
             uniforms: self.resource.uniforms().into_iter().collect(),
             pipeline: self.resource.pipeline.clone(),
+            pipeline_state: self.resource.pipeline_state,
+            profiler: self.resource.state.profiler().clone(),
         }
     }
 }
 
-impl<T: Attribute> ShapeResource<T> {
+impl<C: Context> Resource for BufferResource<C> {}
+
+impl<C> BufferStorage<C> {
+    pub(crate) fn new(resource: BufferResource<C>) -> Self {
+        Self {
+            resource,
+            instance_count: 0,
+        }
+    }
+
+    /// Draws `instances`, the buffer is drawn once even if stored several times.
+    pub(crate) fn store(&mut self, instances: &StorageBuffer<InstanceData>) {
+        self.instance_count = instances.len() as u32;
+    }
+}
+
+impl<C: Context> Storage for BufferStorage<C> {
+    type Context = C;
+    type Resource = BufferResource<C>;
+    type Renderer = ShapeRenderer<C>;
+
+    fn resource(&self) -> Self::Resource {
+        self.resource.clone()
+    }
+    fn bake(&self) -> Self::Renderer {
+        let shape = &self.resource.shape;
+        ShapeRenderer {
+            geometry: shape.vertices.clone(),
+            instance_count: self.instance_count,
+            instance_buffer: InstanceBuffer::Storage(self.resource.instances.clone()),
+            uniforms: shape.uniforms().into_iter().collect(),
+            pipeline: shape.pipeline.clone(),
+            pipeline_state: shape.pipeline_state,
+            profiler: shape.state.profiler().clone(),
+        }
+    }
+}
+
+impl<T: Attribute, C> ShapeResource<T, C> {
     fn uniforms(&self) -> impl IntoIterator<Item = wgpu::BindGroup> {
         [self.texture.bind_group().clone()]
             .into_iter()
@@ -91,21 +166,25 @@ impl<T: Attribute> ShapeResource<T> {
     }
 }
 
-impl Renderer<Camera> for ShapeRenderer {
-    fn render(&self, ctx: &Camera, pass: &mut wgpu::RenderPass<'_>) {
+impl<C: Context> Renderer<C> for ShapeRenderer<C> {
+    fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>) {
         pass.push_debug_group("prepare");
-        pass.set_pipeline(&self.pipeline);
-        for (i, bind_group) in [ctx.bind_group()].iter().chain(&self.uniforms).enumerate() {
-            pass.set_bind_group(i as u32, bind_group, &[]);
+        pass.set_pipeline(&self.pipeline.get(self.pipeline_state));
+        let context = [ctx.bind_group()]
+            .into_iter()
+            .chain(ctx.globals_bind_group());
+        for (i, bind_group) in context.chain(self.uniforms.iter().cloned()).enumerate() {
+            pass.set_bind_group(i as u32, &bind_group, &[]);
         }
         pass.set_vertex_buffer(0, self.geometry.vertices().slice(..));
         if let Some(index_buffer) = self.geometry.indices() {
             pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         }
-        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice());
         pass.pop_debug_group();
 
         pass.insert_debug_marker("draw");
+        self.profiler.record_draw(self.instance_count);
         if self.geometry.indices().is_some() {
             pass.draw_indexed(0..self.geometry.count(), 0, 0..self.instance_count);
         } else {
@@ -3,6 +3,10 @@ use serde::Serialize;
 use wgame_gfx_texture::TextureAttribute;
 use wgame_shader::{Attribute, Binding, BindingList};
 
+/// Customization of the shape shader.
+///
+/// Global uniforms of the context are available in shader code as `globals` variable,
+/// see [`Context::globals`](wgame_gfx::Context::globals).
 #[derive(Clone, Default, Debug, Serialize)]
 pub struct ShaderConfig {
     /// Instance buffer additional attributes.
@@ -1,14 +1,20 @@
 use glam::Affine2;
-use wgame_gfx::{Object, modifiers::Transformable, prelude::Colorable, types::Color};
+use wgame_gfx::{
+    Context, Object,
+    modifiers::{Pipelined, Transformable},
+    prelude::Colorable,
+    types::Color,
+};
 
 use crate::{ShapesLibrary, Texture};
 
-pub trait Shape: Transformable {
-    fn library(&self) -> &ShapesLibrary;
+pub trait Shape: Transformable + Pipelined {
+    type Context: Context;
+    fn library(&self) -> &ShapesLibrary<Self::Context>;
 }
 
 pub trait ShapeFill: Shape {
-    type Fill: Object + Textured + Colorable + Transformable;
+    type Fill: Object<Context = Self::Context> + Textured + Colorable + Transformable + Pipelined;
 
     fn fill_texture(&self, texture: &Texture) -> Self::Fill;
     fn fill_color(&self, color: impl Color) -> Self::Fill {
@@ -17,7 +23,7 @@ pub trait ShapeFill: Shape {
 }
 
 pub trait ShapeStroke: Shape {
-    type Stroke: Object + Textured + Colorable + Transformable;
+    type Stroke: Object<Context = Self::Context> + Textured + Colorable + Transformable + Pipelined;
 
     fn stroke_texture(&self, line_width: f32, texture: &Texture) -> Self::Stroke;
     fn stroke_color(&self, line_width: f32, color: impl Color) -> Self::Stroke {
@@ -34,6 +40,25 @@ pub trait Textured: Colorable + Sized {
 
 #[macro_export]
 macro_rules! impl_textured {
+    ($self:ident<$($param:ident),+>, $texture:ident) => {
+        impl<$($param),+> $crate::Textured for $self<$($param),+> {
+            fn tranform_texcoord(&self, tex_xform: glam::Affine2) -> Self {
+                Self {
+                    $texture: self.$texture.transform_coord(tex_xform),
+                    ..self.clone()
+                }
+            }
+        }
+
+        impl<$($param),+> wgame_gfx::modifiers::Colorable for $self<$($param),+> {
+            fn multiply_color<K: wgame_gfx::types::Color>(&self, color: K) -> Self {
+                Self {
+                    $texture: self.$texture.multiply_color(color),
+                    ..self.clone()
+                }
+            }
+        }
+    };
     ($self:ty, $texture:ident) => {
         impl $crate::Textured for $self {
             fn tranform_texcoord(&self, tex_xform: glam::Affine2) -> Self {
@@ -45,7 +70,7 @@ macro_rules! impl_textured {
         }
 
         impl wgame_gfx::modifiers::Colorable for $self {
-            fn multiply_color<C: wgame_gfx::types::Color>(&self, color: C) -> Self {
+            fn multiply_color<K: wgame_gfx::types::Color>(&self, color: K) -> Self {
                 Self {
                     $texture: self.$texture.multiply_color(color),
                     ..self.clone()
@@ -11,8 +11,8 @@ mod texture;
 use glam::{Affine2, Vec2};
 use half::f16;
 use rgb::Rgba;
-use wgame_gfx::{Graphics, types::Color};
-use wgame_image::{Image, ImageBase, ImageWriteMut};
+use wgame_gfx::{ColorSpace, Graphics, types::Color};
+use wgame_image::{Image, ImageBase, ImageRead, ImageWriteMut};
 
 pub use self::{
     state::TexturingState,
@@ -43,6 +43,14 @@ impl TexturingLibrary {
         }
     }
 
+    /// Recreates GPU resources on the new graphics state, e.g. after device loss.
+    ///
+    /// Textures created by this library remain valid.
+    pub fn recreate(&mut self, state: &Graphics) {
+        self.state = TexturingState::new(state);
+        self.default_atlas.recreate(&self.state);
+    }
+
     /// Returns the texturing state.
     pub fn state(&self) -> &TexturingState {
         &self.state
@@ -55,6 +63,23 @@ impl TexturingLibrary {
         texture
     }
 
+    /// Creates a texture from an sRGB-encoded image, e.g. decoded from PNG.
+    ///
+    /// The image is converted to the color space of the graphics.
+    pub fn texture_srgb(&self, image: &Image<Rgba<f16>>, settings: TextureSettings) -> Texture {
+        match self.state.color_space() {
+            ColorSpace::Srgb => self.texture(image, settings),
+            ColorSpace::Linear => {
+                let data = image
+                    .data()
+                    .iter()
+                    .map(|pixel| pixel.srgb_to_linear().to_rgba_f16())
+                    .collect::<Vec<_>>();
+                self.texture(&Image::with_data(image.size(), data), settings)
+            }
+        }
+    }
+
     /// Creates a 1D gradient texture from an array of colors.
     pub fn gradient<T: Color, const N: usize>(&self, colors: [T; N]) -> Texture {
         self.gradient2([colors])
@@ -167,6 +167,9 @@ impl TextureInstance {
         let bytes_per_block = format.block_copy_size(None).unwrap() as usize;
         assert_eq!(size_of::<T>(), bytes_per_block);
 
+        self.state
+            .profiler()
+            .record_upload((size.width * size.height) as u64 * bytes_per_block as u64);
         self.state.queue().write_texture(
             wgpu::TexelCopyTextureInfo {
                 texture: &self.texture,
@@ -234,6 +237,12 @@ impl<T: Texel> InnerAtlas<T> {
 
         dst.clone()
     }
+
+    fn recreate(&mut self, state: &TexturingState) {
+        self.state = state.clone();
+        self.dst = None;
+        self.tracker.add(Rect::from_size(self.src.size()));
+    }
 }
 
 impl<T: Texel> TextureAtlas<T> {
@@ -256,6 +265,13 @@ impl<T: Texel> TextureAtlas<T> {
     pub fn inner(&self) -> Atlas<T> {
         self.inner.borrow().src.clone()
     }
+
+    /// Moves the atlas to the new state, e.g. after device loss.
+    ///
+    /// GPU texture is uploaded again from the CPU-side atlas, existing textures remain valid.
+    pub fn recreate(&self, state: &TexturingState) {
+        self.inner.borrow_mut().recreate(state);
+    }
 }
 
 impl<T: Texel> Texture<T> {
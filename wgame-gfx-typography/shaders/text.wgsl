@@ -1,3 +1,14 @@
+// Set for blend modes that expect premultiplied colors.
+override premultiply_alpha: bool = false;
+// Set for linear color space if the target format has no sRGB variant.
+override encode_srgb: bool = false;
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
 struct VertexInput {
     @location(0) position: vec4<f32>,
 };
@@ -63,5 +74,11 @@ fn fragment_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
     var color = vec4<f32>(1.0, 1.0, 1.0, value);
     color *= vertex.color;
 
+    if (encode_srgb) {
+        color = vec4<f32>(linear_to_srgb(color.rgb), color.a);
+    }
+    if (premultiply_alpha) {
+        color = vec4<f32>(color.rgb * color.a, color.a);
+    }
     return color;
 }
@@ -1,6 +1,12 @@
-use std::{borrow::Cow, ops::Deref};
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    rc::Rc,
+};
 
 use glam::Vec4;
+use wgame_gfx::{PipelineKey, PipelineState, PipelineVariants, VertexBufferKey};
 use wgame_gfx_texture::{TextureAtlas, TexturingLibrary, TexturingState};
 use wgpu::util::DeviceExt;
 
@@ -11,7 +17,22 @@ pub struct TypographyState {
     pub(crate) inner: TexturingState,
     pub(crate) vertex_buffer: wgpu::Buffer,
     pub(crate) index_buffer: wgpu::Buffer,
-    pub(crate) pipeline: wgpu::RenderPipeline,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    pipelines: Rc<PipelineVariants>,
+}
+
+impl PartialEq for TypographyState {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.pipelines, &other.pipelines)
+    }
+}
+
+impl Eq for TypographyState {}
+
+impl Hash for TypographyState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.pipelines).hash(state);
+    }
 }
 
 impl Deref for TypographyState {
@@ -25,39 +46,6 @@ impl TypographyState {
     const INSTANCE_COMPONENTS: u32 = 6;
 
     pub fn new(state: &TexturingState) -> Self {
-        let device = state.device().clone();
-        let swapchain_format = state.format();
-
-        let shader_source =
-            wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("../shaders/text.wgsl")));
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("text_shader"),
-            source: shader_source,
-        });
-
-        let vertex_buffers_layout = [
-            wgpu::VertexBufferLayout {
-                array_stride: 4 * 4,
-                step_mode: wgpu::VertexStepMode::Vertex,
-                attributes: &[wgpu::VertexAttribute {
-                    shader_location: 0,
-                    offset: 0,
-                    format: wgpu::VertexFormat::Float32x4,
-                }],
-            },
-            wgpu::VertexBufferLayout {
-                array_stride: 4 * 4 * Self::INSTANCE_COMPONENTS as u64,
-                step_mode: wgpu::VertexStepMode::Instance,
-                attributes: &(0..Self::INSTANCE_COMPONENTS)
-                    .map(|i| wgpu::VertexAttribute {
-                        shader_location: i + 1,
-                        offset: 4 * 4 * i as u64,
-                        format: wgpu::VertexFormat::Float32x4,
-                    })
-                    .collect::<Vec<_>>(),
-            },
-        ];
-
         let vertex_buffer = state
             .device()
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -80,45 +68,56 @@ impl TypographyState {
 
         let texture_bind_group_layout = state.bind_group_layout(wgpu::TextureFormat::R8Uint);
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
-            bind_group_layouts: &[state.camera_bind_group_layout(), &texture_bind_group_layout],
-            immediate_size: 0,
-        });
-
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vertex_main"),
-                buffers: &vertex_buffers_layout,
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fragment_main"),
-                compilation_options: Default::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: swapchain_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview_mask: None,
-            cache: None,
-        });
-
         Self {
             inner: state.clone(),
             vertex_buffer,
             index_buffer,
-            pipeline,
+            texture_bind_group_layout,
+            pipelines: Rc::default(),
         }
     }
+
+    /// Returns text pipeline for the given state, creating it if needed.
+    pub(crate) fn pipeline(&self, pipeline_state: PipelineState) -> wgpu::RenderPipeline {
+        self.pipelines
+            .get_or_create(pipeline_state, || self.create_pipeline(pipeline_state))
+    }
+
+    fn create_pipeline(&self, pipeline_state: PipelineState) -> wgpu::RenderPipeline {
+        let vertex_buffers = vec![
+            VertexBufferKey {
+                array_stride: 4 * 4,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: vec![wgpu::VertexAttribute {
+                    shader_location: 0,
+                    offset: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                }],
+            },
+            VertexBufferKey {
+                array_stride: 4 * 4 * Self::INSTANCE_COMPONENTS as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: (0..Self::INSTANCE_COMPONENTS)
+                    .map(|i| wgpu::VertexAttribute {
+                        shader_location: i + 1,
+                        offset: 4 * 4 * i as u64,
+                        format: wgpu::VertexFormat::Float32x4,
+                    })
+                    .collect(),
+            },
+        ];
+
+        self.render_pipeline(&PipelineKey {
+            source: Cow::Borrowed(include_str!("../shaders/text.wgsl")),
+            bind_group_layouts: vec![
+                self.camera_bind_group_layout().clone(),
+                self.texture_bind_group_layout.clone(),
+            ],
+            vertex_buffers,
+            state: pipeline_state,
+            target: None,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -140,6 +139,14 @@ impl TypographyLibrary {
         }
     }
 
+    /// Recreates GPU resources on the new texturing state, e.g. after device loss.
+    ///
+    /// Font textures must be created again, while rasterized glyphs are kept.
+    pub fn recreate(&mut self, texture: &TexturingLibrary) {
+        self.state = TypographyState::new(texture.state());
+        self.default_atlas.recreate(&self.state);
+    }
+
     pub fn texture(&self, font: &FontData, settings: impl Into<RasterSettings>) -> FontTexture {
         let atlas = self.default_atlas.inner();
         let font_atlas = FontAtlas::new(&atlas, font, settings);
@@ -1,31 +1,36 @@
+use std::marker::PhantomData;
+
 use glam::{Mat4, Vec4};
-use wgame_gfx::{Camera, Context, Renderer, Resource, Storage, types::Color};
+use wgame_gfx::{
+    Context, Graphics, PipelineState, PooledBuffer, Profiler, Renderer, Resource, Storage,
+    types::Color,
+};
 use wgame_gfx_texture::TextureResource;
 use wgame_shader::{Attribute, BytesSink};
-use wgpu::util::DeviceExt;
 
-use crate::{FontTexture, text::TextInstance};
+use crate::{FontTexture, TypographyState, text::TextInstance};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct TextResource {
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     texture: TextureResource<u8>,
-    pipeline: wgpu::RenderPipeline,
-    device: wgpu::Device,
+    library: TypographyState,
+    pipeline_state: PipelineState,
+    state: Graphics,
 }
 
 impl TextResource {
-    pub fn new(font: &FontTexture) -> Self {
+    pub fn new(font: &FontTexture, pipeline_state: PipelineState) -> Self {
         let library = &font.library;
-        let pipeline = library.pipeline.clone();
 
         Self {
             vertex_buffer: library.vertex_buffer.clone(),
             index_buffer: library.index_buffer.clone(),
-            pipeline,
+            library: library.clone(),
+            pipeline_state,
             texture: font.inner().resource(),
-            device: library.device().clone(),
+            state: Graphics::clone(library),
         }
     }
 }
@@ -37,16 +42,18 @@ struct GlyphAttribute {
     color: Vec4,
 }
 
-pub struct TextStorage {
+pub struct TextStorage<C> {
     resource: TextResource,
-    pub(crate) instances: Vec<TextInstance>,
+    pub(crate) instances: Vec<TextInstance<C>>,
+    _context: PhantomData<C>,
 }
 
-impl TextStorage {
+impl<C> TextStorage<C> {
     pub(crate) fn new(resource: TextResource) -> Self {
         Self {
             resource,
             instances: Vec::new(),
+            _context: PhantomData,
         }
     }
 }
@@ -55,15 +62,17 @@ pub struct TextRenderer {
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     instance_count: u32,
-    instance_buffer: wgpu::Buffer,
+    instance_buffer: PooledBuffer,
     texture_bind_group: wgpu::BindGroup,
-    pipeline: wgpu::RenderPipeline,
+    library: TypographyState,
+    pipeline_state: PipelineState,
+    profiler: Profiler,
 }
 
 impl Resource for TextResource {}
 
-impl Storage for TextStorage {
-    type Context = Camera;
+impl<C: Context> Storage for TextStorage<C> {
+    type Context = C;
     type Resource = TextResource;
     type Renderer = TextRenderer;
 
@@ -91,37 +100,37 @@ impl Storage for TextStorage {
                 instance_count += 1;
             }
         }
-        let instance_buffer =
-            self.resource
-                .device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("instances"),
-                    contents: bytes.data(),
-                    usage: wgpu::BufferUsages::VERTEX,
-                });
+        let instance_buffer = self
+            .resource
+            .state
+            .buffers()
+            .write(wgpu::BufferUsages::VERTEX, bytes.data());
         TextRenderer {
             vertex_buffer: self.resource.vertex_buffer.clone(),
             index_buffer: self.resource.index_buffer.clone(),
             instance_count,
             instance_buffer,
             texture_bind_group: self.resource.texture.bind_group(),
-            pipeline: self.resource.pipeline.clone(),
+            library: self.resource.library.clone(),
+            pipeline_state: self.resource.pipeline_state,
+            profiler: self.resource.state.profiler().clone(),
         }
     }
 }
 
-impl Renderer<Camera> for TextRenderer {
-    fn render(&self, ctx: &Camera, pass: &mut wgpu::RenderPass<'_>) {
+impl<C: Context> Renderer<C> for TextRenderer {
+    fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>) {
         pass.push_debug_group("prepare");
-        pass.set_pipeline(&self.pipeline);
+        pass.set_pipeline(&self.library.pipeline(self.pipeline_state));
         pass.set_bind_group(0, &ctx.bind_group(), &[]);
         pass.set_bind_group(1, &self.texture_bind_group, &[]);
         pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
         pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice());
         pass.pop_debug_group();
 
         pass.insert_debug_marker("draw");
+        self.profiler.record_draw(self.instance_count);
         pass.draw_indexed(0..6, 0, 0..self.instance_count);
     }
 }
@@ -1,9 +1,11 @@
-use std::rc::Rc;
+use std::{marker::PhantomData, rc::Rc};
 
+use derivative::Derivative;
 use glam::{Affine3A, Mat4, Quat, Vec3};
 use rgb::Rgba;
 use wgame_gfx::{
-    Camera, Instance, InstanceVisitor, Object,
+    Bounds, Camera, Context, Instance, InstanceVisitor, Node, Object, PipelineState,
+    impl_pipelined,
     modifiers::{Colorable, Transformable},
     types::{Color, Transform, color},
 };
@@ -23,16 +25,19 @@ pub enum TextAlign {
 }
 
 #[must_use]
-#[derive(Clone)]
-pub struct Text {
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Text<C = Camera> {
     font: FontTexture,
     metrics: Rc<TextMetrics>,
     xform: Affine3A,
     color: Rgba<f32>,
     align: TextAlign,
+    pipeline_state: PipelineState,
+    _context: PhantomData<C>,
 }
 
-impl Text {
+impl<C: Context> Text<C> {
     pub fn new(font: &FontTexture, text: &str) -> Self {
         Self::from_metrics(
             font,
@@ -47,6 +52,8 @@ impl Text {
             metrics: Rc::new(metrics),
             color: color::WHITE.to_rgba(),
             align: TextAlign::default(),
+            pipeline_state: PipelineState::default(),
+            _context: PhantomData,
         }
     }
 
@@ -64,7 +71,13 @@ impl Text {
         }
     }
 
-    pub fn instance(&self) -> Option<TextInstance> {
+    pub fn instance(&self) -> Option<TextInstance<C>> {
+        self.transformed_instance(Affine3A::IDENTITY, color::WHITE.to_rgba())
+    }
+
+    /// Instance of the text transformed by `xform` and multiplied by `color`.
+    fn transformed_instance(&self, xform: Affine3A, color: Rgba<f32>) -> Option<TextInstance<C>> {
+        let xform = xform * self.xform;
         let width = self.metrics.width();
         let mut offset = match self.align {
             TextAlign::Left => 0.0,
@@ -75,7 +88,7 @@ impl Text {
         for glyph in self.metrics.glyphs() {
             if let Some(glyph_image) = self.font.glyph_info(glyph.id) {
                 glyphs.push(GlyphInstance {
-                    xform: (self.xform
+                    xform: (xform
                         * Affine3A::from_scale_rotation_translation(
                             Vec3::new(
                                 glyph_image.placement.width as f32,
@@ -101,13 +114,15 @@ impl Text {
             Some(TextInstance {
                 texture: self.font.clone(),
                 glyphs: glyphs.into(),
-                color: self.color,
+                color: color.mul(self.color),
+                pipeline_state: self.pipeline_state,
+                _context: PhantomData,
             })
         }
     }
 }
 
-impl Transformable for Text {
+impl<C> Transformable for Text<C> {
     fn transform<X: Transform>(&self, xform: X) -> Self {
         Self {
             xform: xform.to_affine3() * self.xform,
@@ -116,8 +131,8 @@ impl Transformable for Text {
     }
 }
 
-impl Colorable for Text {
-    fn multiply_color<C: Color>(&self, color: C) -> Self {
+impl<C> Colorable for Text<C> {
+    fn multiply_color<K: Color>(&self, color: K) -> Self {
         Self {
             color: self.color.mul(color),
             ..self.clone()
@@ -125,11 +140,16 @@ impl Colorable for Text {
     }
 }
 
-#[derive(Clone)]
-pub struct TextInstance {
+impl_pipelined!(Text<C>, pipeline_state);
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct TextInstance<C = Camera> {
     pub(crate) texture: FontTexture,
     pub(crate) glyphs: Rc<[GlyphInstance]>,
     pub(crate) color: Rgba<f32>,
+    pub(crate) pipeline_state: PipelineState,
+    _context: PhantomData<C>,
 }
 
 pub(crate) struct GlyphInstance {
@@ -137,13 +157,13 @@ pub(crate) struct GlyphInstance {
     pub(crate) id: GlyphId,
 }
 
-impl Instance for TextInstance {
-    type Context = Camera;
+impl<C: Context> Instance for TextInstance<C> {
+    type Context = C;
     type Resource = TextResource;
-    type Storage = TextStorage;
+    type Storage = TextStorage<C>;
 
     fn resource(&self) -> Self::Resource {
-        TextResource::new(&self.texture)
+        TextResource::new(&self.texture, self.pipeline_state)
     }
     fn new_storage(&self) -> Self::Storage {
         TextStorage::new(self.resource())
@@ -151,20 +171,41 @@ impl Instance for TextInstance {
     fn store(&self, storage: &mut Self::Storage) {
         storage.instances.push(self.clone());
     }
+    fn bounds(&self) -> Option<Bounds> {
+        // Glyph quad spans from 0 to 1 in local coordinates.
+        let quad = Bounds::new(Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0));
+        self.glyphs
+            .iter()
+            .map(|glyph| quad.transform(Affine3A::from_mat4(glyph.xform)))
+            .reduce(Bounds::union)
+    }
 }
 
-impl Object for TextInstance {
-    type Context = Camera;
+impl<C: Context> Object for TextInstance<C> {
+    type Context = C;
     fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
         visitor.visit(self);
     }
 }
 
-impl Object for Text {
-    type Context = Camera;
+impl<C: Context> Object for Text<C> {
+    type Context = C;
     fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
         if let Some(instance) = self.instance() {
             visitor.visit(&instance);
         }
     }
 }
+
+impl<C: Context> Node for Text<C> {
+    fn for_each_transformed<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    ) {
+        if let Some(instance) = self.transformed_instance(xform, color) {
+            visitor.visit(&instance);
+        }
+    }
+}
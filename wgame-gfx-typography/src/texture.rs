@@ -1,5 +1,6 @@
 use std::ops::Deref;
 
+use wgame_gfx::Context;
 use wgame_gfx_texture::{Texture, TextureAtlas};
 
 use crate::{FontAtlas, Text, TypographyState};
@@ -36,7 +37,7 @@ impl FontTexture {
         }
     }
 
-    pub fn text(&self, text: &str) -> Text {
+    pub fn text<C: Context>(&self, text: &str) -> Text<C> {
         Text::new(self, text)
     }
 
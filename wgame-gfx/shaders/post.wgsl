@@ -0,0 +1,68 @@
+struct Uniforms {
+    texel_size: vec2<f32>,
+    params: vec4<f32>,
+};
+
+struct VaryingData {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var source_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var original_texture: texture_2d<f32>;
+@group(0) @binding(2)
+var extra_texture: texture_2d<f32>;
+@group(0) @binding(3)
+var linear_sampler: sampler;
+@group(0) @binding(4)
+var<uniform> uniforms: Uniforms;
+
+fn sample_source(tex_coord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(source_texture, linear_sampler, tex_coord, 0.0);
+}
+
+fn sample_original(tex_coord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(original_texture, linear_sampler, tex_coord, 0.0);
+}
+
+fn sample_extra(tex_coord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(extra_texture, linear_sampler, tex_coord, 0.0);
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+{{ source }}
+
+@vertex
+fn vertex_main(@builtin(vertex_index) index: u32) -> VaryingData {
+    // Single triangle covering the whole target.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var output: VaryingData;
+    output.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    output.tex_coord = uv;
+    return output;
+}
+
+@fragment
+fn fragment_main(input: VaryingData) -> @location(0) vec4<f32> {
+    let tex_coord = input.tex_coord;
+    let texel_size = uniforms.texel_size;
+    let params = uniforms.params;
+    var color = sample_source(tex_coord);
+
+    {{ fragment_source }}
+
+    return color;
+}
@@ -1,11 +1,14 @@
 use std::ops::{Deref, DerefMut};
 
-use crate::{Camera, Context, Scene, Target};
+use web_time::Instant;
+
+use crate::{Camera, Context, Object, Scene, Target};
 
 pub struct AutoScene<'a, T: Target + ?Sized, C: Context = Camera> {
     pub target: &'a mut T,
     pub camera: C,
     pub items: Scene<C>,
+    started: Instant,
 }
 
 impl<'a, T: Target + ?Sized, C: Context> AutoScene<'a, T, C> {
@@ -14,9 +17,16 @@ impl<'a, T: Target + ?Sized, C: Context> AutoScene<'a, T, C> {
             target,
             camera: context,
             items: Scene::default(),
+            started: Instant::now(),
         }
     }
 
+    /// Adds only those instances of `object` that are visible by the camera,
+    /// see [`Scene::add_visible`].
+    pub fn add_visible<O: Object<Context = C>>(&mut self, object: &O) {
+        self.items.add_visible(object, &self.camera);
+    }
+
     pub fn discard(mut self) {
         self.items = Scene::default();
     }
@@ -38,7 +48,16 @@ impl<T: Target + ?Sized, C: Context> DerefMut for AutoScene<'_, T, C> {
 impl<T: Target + ?Sized, C: Context> Drop for AutoScene<'_, T, C> {
     fn drop(&mut self) {
         if !self.items.is_empty() {
-            self.target.render_iter(&self.camera, self.items.iter());
+            let profiler = self.target.profiler().clone();
+            let _active = self.target.state().profiler().activate(&profiler);
+            profiler.record_scene_time(self.started);
+            let renderers: Vec<_> = profiler.time_bake(|| {
+                self.items
+                    .iter()
+                    .map(|storage| storage.bake_dyn())
+                    .collect()
+            });
+            self.target.render_iter(&self.camera, renderers.iter());
         }
     }
 }
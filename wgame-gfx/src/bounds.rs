@@ -0,0 +1,70 @@
+use glam::{Mat4, Vec3, Vec4};
+
+use crate::types::Transform;
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Bounds {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Smallest box containing all `points`, `None` if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, p| {
+            Some(match bounds {
+                None => Self::new(p, p),
+                Some(Self { min, max }) => Self::new(min.min(p), max.max(p)),
+            })
+        })
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let Self { min, max } = *self;
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// Bounding box of `self` transformed by `xform`.
+    pub fn transform<X: Transform>(&self, xform: X) -> Self {
+        let xform = xform.to_affine3();
+        let corners = self.corners().map(|p| xform.transform_point3(p));
+        Self::from_points(corners).unwrap()
+    }
+
+    /// Checks whether the box intersects the view volume of `view` matrix that maps to clip space.
+    ///
+    /// The check is conservative, so the box may be reported visible while being outside of the volume.
+    pub fn is_visible(&self, view: Mat4) -> bool {
+        let corners = self.corners().map(|p| view * Vec4::from((p, 1.0)));
+        // Box is invisible if all its corners are outside of the same clip plane.
+        let planes: [fn(Vec4) -> bool; 6] = [
+            |p| p.x < -p.w,
+            |p| p.x > p.w,
+            |p| p.y < -p.w,
+            |p| p.y > p.w,
+            |p| p.z < 0.0,
+            |p| p.z > p.w,
+        ];
+        !planes
+            .iter()
+            .any(|outside| corners.iter().all(|&p| outside(p)))
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use hashbrown::HashMap;
+
+use crate::{Graphics, PipelineState};
+
+/// Render pipeline description that identifies the pipeline in the cache of [`Graphics`].
+///
+/// Pipeline renders into targets of the graphics, using `vertex_main` and `fragment_main` entry points of the shader.
+/// If [`BlendMode::premultiplies_in_shader`](crate::BlendMode::premultiplies_in_shader) for the blend mode of the `state`,
+/// the shader must declare `override premultiply_alpha: bool` constant and premultiply output colors when it is set.
+/// If the graphics renders in [`ColorSpace::Linear`](crate::ColorSpace::Linear) into a format without sRGB variant
+/// and the pipeline renders into targets of the graphics, the shader must declare `override encode_srgb: bool` constant
+/// and encode output colors into sRGB before premultiplying.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PipelineKey {
+    /// Substituted WGSL source of the shader.
+    pub source: Cow<'static, str>,
+    pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    pub vertex_buffers: Vec<VertexBufferKey>,
+    pub state: PipelineState,
+    /// Format of single-sampled textures without depth buffer that the pipeline renders into instead of targets of the graphics,
+    /// e.g. intermediate textures of post-processing.
+    pub target: Option<wgpu::TextureFormat>,
+}
+
+/// Owned [`wgpu::VertexBufferLayout`].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct VertexBufferKey {
+    pub array_stride: u64,
+    pub step_mode: wgpu::VertexStepMode,
+    pub attributes: Vec<wgpu::VertexAttribute>,
+}
+
+/// Render pipelines and bind group layouts shared by all clones of [`Graphics`].
+#[derive(Clone)]
+pub(crate) struct PipelineCache {
+    inner: Arc<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    pipelines: Mutex<HashMap<PipelineKey, wgpu::RenderPipeline>>,
+    bind_group_layouts: Mutex<HashMap<Vec<wgpu::BindGroupLayoutEntry>, wgpu::BindGroupLayout>>,
+}
+
+impl PipelineCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::default(),
+        }
+    }
+
+    pub(crate) fn render_pipeline(
+        &self,
+        state: &Graphics,
+        key: &PipelineKey,
+    ) -> wgpu::RenderPipeline {
+        if let Some(pipeline) = self.inner.pipelines.lock().unwrap().get(key) {
+            return pipeline.clone();
+        }
+        let pipeline = self.create_render_pipeline(state, key);
+        (self.inner.pipelines.lock().unwrap()).insert(key.clone(), pipeline.clone());
+        pipeline
+    }
+
+    fn create_render_pipeline(&self, state: &Graphics, key: &PipelineKey) -> wgpu::RenderPipeline {
+        let device = state.device();
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(key.source.clone()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &key.bind_group_layouts.iter().collect::<Vec<_>>(),
+            immediate_size: 0,
+        });
+        let vertex_buffers = key
+            .vertex_buffers
+            .iter()
+            .map(|buffer| wgpu::VertexBufferLayout {
+                array_stride: buffer.array_stride,
+                step_mode: buffer.step_mode,
+                attributes: &buffer.attributes,
+            })
+            .collect::<Vec<_>>();
+
+        let mut constants = Vec::new();
+        if key.state.blend.premultiplies_in_shader() {
+            constants.push(("premultiply_alpha", 1.0));
+        }
+        if key.target.is_none() && state.color_space().encodes_in_shader(state.format()) {
+            constants.push(("encode_srgb", 1.0));
+        }
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vertex_main"),
+                buffers: &vertex_buffers,
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fragment_main"),
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &constants,
+                    ..Default::default()
+                },
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: key.target.unwrap_or(state.format()),
+                    blend: key.state.blend.blend_state(),
+                    write_mask: key.state.color_writes(),
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: match key.target {
+                Some(_) => None,
+                None => key.state.depth_stencil(state),
+            },
+            multisample: wgpu::MultisampleState {
+                count: match key.target {
+                    Some(_) => 1,
+                    None => state.sample_count(),
+                },
+                ..Default::default()
+            },
+            multiview_mask: None,
+            cache: None,
+        })
+    }
+
+    pub(crate) fn bind_group_layout(
+        &self,
+        device: &wgpu::Device,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> wgpu::BindGroupLayout {
+        if let Some(layout) = self.inner.bind_group_layouts.lock().unwrap().get(entries) {
+            return layout.clone();
+        }
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries,
+        });
+        (self.inner.bind_group_layouts.lock().unwrap()).insert(entries.to_vec(), layout.clone());
+        layout
+    }
+}
+
+impl fmt::Debug for PipelineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineCache")
+            .field("len", &self.inner.pipelines.lock().unwrap().len())
+            .finish_non_exhaustive()
+    }
+}
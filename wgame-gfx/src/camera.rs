@@ -1,21 +1,29 @@
-use std::{cell::RefCell, num::NonZero};
+use std::{cell::RefCell, marker::PhantomData, num::NonZero, rc::Rc};
 
+use derivative::Derivative;
 use glam::{Mat4, Vec4};
 use rgb::Rgba;
-use wgpu::util::DeviceExt;
+use wgame_shader::{Attribute, BindingList};
 
 use crate::{
-    Context, Graphics,
+    Bounds, Context, Graphics, PooledBuffer,
     prelude::{Colorable, Transformable},
     types::{Color, Transform, color},
 };
 
-#[derive(Clone, Debug)]
-pub struct Camera {
+/// Camera that passes global uniforms of type `G` to shaders, see [`Camera::with_globals`].
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Camera<G = ()> {
     state: Graphics,
-    bind_group: RefCell<Option<wgpu::BindGroup>>,
+    /// Bind group cached together with the buffer it refers to.
+    bind_group: RefCell<Option<(PooledBuffer, wgpu::BindGroup)>>,
     view: Mat4,
     color: Rgba<f32>,
+    /// Global uniforms laid out as a uniform struct.
+    globals: Rc<[u8]>,
+    globals_bind_group: RefCell<Option<(PooledBuffer, wgpu::BindGroup)>>,
+    _globals: PhantomData<G>,
 }
 
 impl Camera {
@@ -25,23 +33,12 @@ impl Camera {
             bind_group: RefCell::default(),
             view,
             color: color::WHITE.to_rgba(),
+            globals: Rc::new([]),
+            globals_bind_group: RefCell::default(),
+            _globals: PhantomData,
         }
     }
 
-    pub fn view(&self) -> Mat4 {
-        self.view
-    }
-    pub fn color(&self) -> Rgba<f32> {
-        self.color
-    }
-
-    pub fn world_to_logical(&self, pos: Vec4) -> Vec4 {
-        self.view.mul_vec4(pos)
-    }
-    pub fn logical_to_world(&self, pos: Vec4) -> Vec4 {
-        self.view.inverse_or_zero().mul_vec4(pos)
-    }
-
     pub(crate) fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("wgame_camera_bind_group_layout"),
@@ -71,7 +68,36 @@ impl Camera {
     }
 }
 
-impl Transformable for Camera {
+impl<G> Camera<G> {
+    /// Passes `globals` to shaders in a separate bind group, see [`Context::globals`].
+    pub fn with_globals<T: Attribute>(&self, globals: &T) -> Camera<T> {
+        Camera {
+            state: self.state.clone(),
+            bind_group: self.bind_group.clone(),
+            view: self.view,
+            color: self.color,
+            globals: globals.to_uniform_bytes().into(),
+            globals_bind_group: RefCell::default(),
+            _globals: PhantomData,
+        }
+    }
+
+    pub fn view(&self) -> Mat4 {
+        self.view
+    }
+    pub fn color(&self) -> Rgba<f32> {
+        self.color
+    }
+
+    pub fn world_to_logical(&self, pos: Vec4) -> Vec4 {
+        self.view.mul_vec4(pos)
+    }
+    pub fn logical_to_world(&self, pos: Vec4) -> Vec4 {
+        self.view.inverse_or_zero().mul_vec4(pos)
+    }
+}
+
+impl<G> Transformable for Camera<G> {
     fn transform<X: Transform>(&self, xform: X) -> Self {
         Self {
             view: self.view * xform.to_mat4(),
@@ -81,7 +107,7 @@ impl Transformable for Camera {
     }
 }
 
-impl Colorable for Camera {
+impl<G> Colorable for Camera<G> {
     fn multiply_color<C: Color>(&self, color: C) -> Self {
         let x = self.color;
         let y = color.to_rgba();
@@ -98,52 +124,70 @@ impl Colorable for Camera {
     }
 }
 
-impl Context for Camera {
+impl<G: Attribute> Context for Camera<G> {
     fn bind_group(&self) -> wgpu::BindGroup {
         let mut bind_group = self.bind_group.borrow_mut();
         bind_group
             .get_or_insert_with(|| {
-                let view_buffer =
-                    self.state
-                        .device()
-                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                            label: None,
-                            contents: bytemuck::cast_slice(&self.view.to_cols_array()),
-                            usage: wgpu::BufferUsages::UNIFORM,
-                        });
-                let color_buffer =
+                // View and color are stored in single buffer, color offset must be properly aligned.
+                let color_offset = (size_of::<Mat4>() as u64).next_multiple_of(
                     self.state
                         .device()
-                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                            label: None,
-                            contents: bytemuck::cast_slice(&self.color.to_vec4().to_array()),
-                            usage: wgpu::BufferUsages::UNIFORM,
-                        });
-                self.state
-                    .device()
-                    .create_bind_group(&wgpu::BindGroupDescriptor {
-                        label: Some("camera"),
-                        layout: self.state.camera_bind_group_layout(),
-                        entries: &[
-                            wgpu::BindGroupEntry {
-                                binding: 0,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &view_buffer,
-                                    offset: 0,
-                                    size: None,
-                                }),
-                            },
-                            wgpu::BindGroupEntry {
-                                binding: 1,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &color_buffer,
-                                    offset: 0,
-                                    size: None,
-                                }),
-                            },
-                        ],
-                    })
+                        .limits()
+                        .min_uniform_buffer_offset_alignment as u64,
+                );
+                let mut contents = vec![0; color_offset as usize + size_of::<Vec4>()];
+                contents[..size_of::<Mat4>()]
+                    .copy_from_slice(bytemuck::cast_slice(&self.view.to_cols_array()));
+                contents[color_offset as usize..]
+                    .copy_from_slice(bytemuck::cast_slice(&self.color.to_vec4().to_array()));
+                let buffer = self
+                    .state
+                    .buffers()
+                    .write(wgpu::BufferUsages::UNIFORM, &contents);
+
+                let bind_group = buffer.bind_group(
+                    self.state.device(),
+                    self.state.camera_bind_group_layout(),
+                    &[
+                        (0, 0..size_of::<Mat4>() as u64),
+                        (1, color_offset..color_offset + size_of::<Vec4>() as u64),
+                    ],
+                );
+                (buffer, bind_group)
             })
+            .1
             .clone()
     }
+
+    fn globals() -> Option<BindingList> {
+        let bindings = G::bindings();
+        (bindings.len() > 0).then_some(bindings)
+    }
+
+    fn globals_bind_group(&self) -> Option<wgpu::BindGroup> {
+        let globals = Self::globals()?;
+        let mut bind_group = self.globals_bind_group.borrow_mut();
+        Some(
+            bind_group
+                .get_or_insert_with(|| {
+                    let buffer = self
+                        .state
+                        .buffers()
+                        .write(wgpu::BufferUsages::UNIFORM, &self.globals);
+                    let bind_group = buffer.bind_group(
+                        self.state.device(),
+                        &self.state.globals_bind_group_layout(&globals),
+                        &[(0, 0..self.globals.len() as u64)],
+                    );
+                    (buffer, bind_group)
+                })
+                .1
+                .clone(),
+        )
+    }
+
+    fn is_visible(&self, bounds: &Bounds) -> bool {
+        bounds.is_visible(self.view)
+    }
 }
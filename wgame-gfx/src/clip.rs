@@ -0,0 +1,159 @@
+use crate::{Bounds, Context, Instance, InstanceVisitor, Object, Renderer, Resource, Storage};
+
+/// Rectangle in physical pixels of the render target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ClipRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub fn new((x, y): (u32, u32), (width, height): (u32, u32)) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Common part of both rectangles.
+    ///
+    /// Empty intersection is placed inside of both rectangles' bounds, so it can be used as a scissor.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let x_end = self.x_end().min(other.x_end());
+        let y_end = self.y_end().min(other.y_end());
+        let (x, y) = (
+            self.x.max(other.x).min(x_end),
+            self.y.max(other.y).min(y_end),
+        );
+        Self::new((x, y), (x_end - x, y_end - y))
+    }
+
+    fn x_end(&self) -> u32 {
+        self.x.saturating_add(self.width)
+    }
+    fn y_end(&self) -> u32 {
+        self.y.saturating_add(self.height)
+    }
+
+    /// Moves the rectangle by the `offset`.
+    pub fn offset(&self, (x, y): (u32, u32)) -> Self {
+        Self {
+            x: self.x.saturating_add(x),
+            y: self.y.saturating_add(y),
+            ..*self
+        }
+    }
+
+    /// Clamps the rectangle to the target of the given size.
+    pub fn clamp(&self, size: (u32, u32)) -> Self {
+        self.intersect(&Self::new((0, 0), size))
+    }
+}
+
+/// Object that is drawn only inside the clip rectangle.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Clipped<T: ?Sized> {
+    pub rect: ClipRect,
+    pub inner: T,
+}
+
+impl<T> Clipped<T> {
+    pub fn new(inner: T, rect: ClipRect) -> Self {
+        Self { inner, rect }
+    }
+}
+
+/// Intersection of the clip rectangle with the inner one, if any.
+fn merge(rect: ClipRect, inner: Option<ClipRect>) -> ClipRect {
+    match inner {
+        Some(inner) => rect.intersect(&inner),
+        None => rect,
+    }
+}
+
+impl<R: Resource> Resource for Clipped<R> {
+    fn order(&self) -> impl Iterator<Item = i32> {
+        self.inner.order()
+    }
+    fn scissor(&self) -> Option<ClipRect> {
+        Some(merge(self.rect, self.inner.scissor()))
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.inner.stencil_reference()
+    }
+}
+
+/// Renderer of [`Clipped`] storage.
+pub struct ClippedRenderer<R> {
+    rect: ClipRect,
+    inner: R,
+}
+
+impl<C: Context, R: Renderer<C>> Renderer<C> for ClippedRenderer<R> {
+    fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>) {
+        self.inner.render(ctx, pass);
+    }
+    fn scissor(&self) -> Option<ClipRect> {
+        Some(merge(self.rect, self.inner.scissor()))
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.inner.stencil_reference()
+    }
+}
+
+impl<S: Storage> Storage for Clipped<S> {
+    type Context = S::Context;
+    type Resource = Clipped<S::Resource>;
+    type Renderer = ClippedRenderer<S::Renderer>;
+
+    fn resource(&self) -> Self::Resource {
+        Clipped::new(self.inner.resource(), self.rect)
+    }
+    fn bake(&self) -> Self::Renderer {
+        ClippedRenderer {
+            rect: self.rect,
+            inner: self.inner.bake(),
+        }
+    }
+}
+
+impl<T: Instance + ?Sized> Instance for Clipped<T> {
+    type Context = T::Context;
+    type Resource = Clipped<T::Resource>;
+    type Storage = Clipped<T::Storage>;
+
+    fn resource(&self) -> Self::Resource {
+        Clipped::new(self.inner.resource(), self.rect)
+    }
+    fn new_storage(&self) -> Self::Storage {
+        Clipped::new(self.inner.new_storage(), self.rect)
+    }
+    fn store(&self, storage: &mut Self::Storage) {
+        self.inner.store(&mut storage.inner);
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        self.inner.bounds()
+    }
+}
+
+impl<T: Object + ?Sized> Object for Clipped<T> {
+    type Context = T::Context;
+    fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
+        self.inner
+            .for_each_instance(&mut Clipped::new(visitor, self.rect));
+    }
+}
+
+impl<C: Context, V: InstanceVisitor<C>> InstanceVisitor<C> for Clipped<V> {
+    fn visit<T: Instance<Context = C>>(&mut self, instance: &T) {
+        self.inner.visit(&Clipped::new(instance, self.rect));
+    }
+}
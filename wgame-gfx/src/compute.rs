@@ -0,0 +1,216 @@
+use std::{borrow::Cow, fmt::Debug, marker::PhantomData};
+
+use anyhow::{Result, anyhow};
+use derivative::Derivative;
+use futures::channel::oneshot;
+use serde::Serialize;
+use wgame_shader::{Attribute, ShaderSource};
+
+use crate::Graphics;
+
+/// Compute shader pipeline.
+#[derive(Clone, Debug)]
+pub struct ComputePipeline {
+    state: Graphics,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl ComputePipeline {
+    /// Creates pipeline from `source` substituted with `ctx`, running `entry_point` function.
+    ///
+    /// Bind group layouts are derived from the shader code.
+    pub fn new<S: Serialize + Debug>(
+        state: &Graphics,
+        source: &ShaderSource,
+        ctx: &S,
+        entry_point: &str,
+    ) -> Result<Self> {
+        let device = state.device();
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("compute_shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source.substitute(ctx)?)),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("compute"),
+            layout: None,
+            module: &module,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        Ok(Self {
+            state: state.clone(),
+            pipeline,
+        })
+    }
+
+    pub fn pipeline(&self) -> &wgpu::ComputePipeline {
+        &self.pipeline
+    }
+
+    /// Creates bind group `index` with `buffers` bound in order, starting from binding `0`.
+    pub fn bind_group(&self, index: u32, buffers: &[&wgpu::Buffer]) -> wgpu::BindGroup {
+        let entries = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| wgpu::BindGroupEntry {
+                binding: i as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
+        self.state
+            .device()
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("compute"),
+                layout: &self.pipeline.get_bind_group_layout(index),
+                entries: &entries,
+            })
+    }
+
+    /// Records dispatch of `workgroups` into `encoder`, see also [`Target::compute`](crate::Target::compute).
+    ///
+    /// Bind groups are set in order, starting from group `0`.
+    pub fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: &[&wgpu::BindGroup],
+        workgroups: (u32, u32, u32),
+    ) {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("compute"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        for (i, bind_group) in bind_groups.iter().enumerate() {
+            pass.set_bind_group(i as u32, *bind_group, &[]);
+        }
+        let (x, y, z) = workgroups;
+        pass.dispatch_workgroups(x, y, z);
+    }
+
+    /// Dispatches `workgroups` in a standalone command encoder and submits it immediately.
+    pub fn dispatch_now(&self, bind_groups: &[&wgpu::BindGroup], workgroups: (u32, u32, u32)) {
+        let mut encoder = self
+            .state
+            .device()
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        self.dispatch(&mut encoder, bind_groups, workgroups);
+        self.state.queue().submit(Some(encoder.finish()));
+    }
+}
+
+/// Buffer of `T` items for compute shaders, that can also be used as a vertex or instance buffer.
+///
+/// Items are packed as by [`Attribute::store`], see [`Self::wgsl`] for declaring them in shaders.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct StorageBuffer<T: Attribute> {
+    state: Graphics,
+    buffer: wgpu::Buffer,
+    len: usize,
+    _ghost: PhantomData<fn() -> T>,
+}
+
+impl<T: Attribute> StorageBuffer<T> {
+    /// Creates buffer of `len` zeroed items.
+    pub fn new(state: &Graphics, len: usize) -> Self {
+        let size = ((len * T::SIZE) as u64)
+            .max(wgpu::COPY_BUFFER_ALIGNMENT)
+            .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+        let buffer = state.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("storage"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        Self {
+            state: state.clone(),
+            buffer,
+            len,
+            _ghost: PhantomData,
+        }
+    }
+
+    pub fn from_items(state: &Graphics, items: &[T]) -> Self {
+        let this = Self::new(state, items.len());
+        this.write(0, items);
+        this
+    }
+
+    /// Writes `items` starting from item `start`.
+    ///
+    /// Buffer writes must be aligned to [`wgpu::COPY_BUFFER_ALIGNMENT`], so if the item size is not a multiple of it,
+    /// both `start` and the end of the written items must be at aligned byte offsets, or the end must be the end of the buffer.
+    pub fn write(&self, start: usize, items: &[T]) {
+        let end = start + items.len();
+        assert!(
+            end <= self.len,
+            "Items {start}..{end} are out of buffer of length {}",
+            self.len
+        );
+        let align = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+        assert!(
+            (start * T::SIZE).is_multiple_of(align)
+                && (end == self.len || (end * T::SIZE).is_multiple_of(align)),
+            "Items {start}..{end} of size {} are not aligned to {align} bytes",
+            T::SIZE
+        );
+        let mut data = Vec::with_capacity(items.len() * T::SIZE);
+        for item in items {
+            data.extend(item.to_bytes());
+        }
+        // Padding goes into the unused tail of the buffer.
+        data.resize(data.len().next_multiple_of(align), 0);
+        self.state
+            .queue()
+            .write_buffer(&self.buffer, (start * T::SIZE) as u64, &data);
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// WGSL declarations of the item type named `name`, see [`BindingList::to_wgsl_storage`](wgame_shader::BindingList::to_wgsl_storage).
+    pub fn wgsl(name: &str) -> Result<String> {
+        T::bindings().to_wgsl_storage(name)
+    }
+
+    /// Reads packed contents of the buffer.
+    ///
+    /// On native platforms this blocks until the GPU finishes all submitted work.
+    pub async fn read_bytes(&self) -> Result<Vec<u8>> {
+        let device = self.state.device();
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: self.buffer.size(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, None);
+        self.state.queue().submit(Some(encoder.finish()));
+
+        let (sender, receiver) = oneshot::channel();
+        staging.map_async(wgpu::MapMode::Read, .., move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::PollType::wait_indefinitely())?;
+        receiver
+            .await
+            .map_err(|_| anyhow!("Buffer mapping was cancelled"))??;
+        let data = staging.get_mapped_range(..)[..self.len * T::SIZE].to_vec();
+        staging.unmap();
+        Ok(data)
+    }
+}
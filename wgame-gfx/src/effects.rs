@@ -0,0 +1,143 @@
+//! Built-in post-processing effects for [`PostProcess`](crate::PostProcess).
+//!
+//! Effects that consist of several passes are returned as vectors that should be pushed into the chain in order.
+
+use anyhow::Result;
+use glam::Vec4;
+
+use crate::{EffectSource, Graphics, PostEffect};
+
+/// Gaussian blur of 9 taps that are sampled in 5 fetches using linear filtering.
+///
+/// Samples are taken along `params.xy` direction measured in texels.
+const GAUSSIAN: &str = "
+    fn gaussian(tex_coord: vec2<f32>, step: vec2<f32>) -> vec4<f32> {
+        let near = step * 1.3846153846;
+        let far = step * 3.2307692308;
+        return sample_source(tex_coord) * 0.2270270270
+            + (sample_source(tex_coord + near) + sample_source(tex_coord - near)) * 0.3162162162
+            + (sample_source(tex_coord + far) + sample_source(tex_coord - far)) * 0.0702702703;
+    }
+";
+
+fn blur_pass(state: &Graphics, direction: Vec4, scale: f32) -> Result<PostEffect> {
+    let source = EffectSource {
+        source: GAUSSIAN.to_string(),
+        fragment_source: "color = gaussian(tex_coord, params.xy * texel_size);".to_string(),
+    };
+    Ok(PostEffect::new(state, &source)?
+        .with_params(direction)
+        .with_scale(scale))
+}
+
+/// Separable gaussian blur, `radius` is a distance between samples in texels.
+///
+/// Blur is done in two passes at `scale` of the target resolution,
+/// lower scale makes the blur wider and cheaper.
+pub fn blur(state: &Graphics, radius: f32, scale: f32) -> Result<Vec<PostEffect>> {
+    Ok(vec![
+        blur_pass(state, Vec4::new(radius, 0.0, 0.0, 0.0), scale)?,
+        blur_pass(state, Vec4::new(0.0, radius, 0.0, 0.0), scale)?,
+    ])
+}
+
+/// Glow around colors brighter than `threshold`, added to the scene with `intensity`.
+///
+/// Highlights are extracted and blurred at half and quarter resolution.
+/// The last pass adds them to the original scene, so bloom should precede other effects in the chain.
+pub fn bloom(state: &Graphics, threshold: f32, intensity: f32) -> Result<Vec<PostEffect>> {
+    let bright = EffectSource {
+        source: String::new(),
+        fragment_source: "
+            // Average of 2x2 source texels.
+            let half = texel_size * 0.5;
+            let average = (
+                sample_source(tex_coord + vec2(-half.x, -half.y))
+                + sample_source(tex_coord + vec2(half.x, -half.y))
+                + sample_source(tex_coord + vec2(-half.x, half.y))
+                + sample_source(tex_coord + vec2(half.x, half.y))
+            ).rgb * 0.25;
+            let brightness = max(max(average.r, average.g), average.b);
+            let factor = max(brightness - params.x, 0.0) / max(brightness, 1e-4);
+            color = vec4(average * factor, 1.0);
+        "
+        .to_string(),
+    };
+    let composite = EffectSource {
+        source: String::new(),
+        fragment_source: "
+            let original = sample_original(tex_coord);
+            color = vec4(original.rgb + color.rgb * params.x, original.a);
+        "
+        .to_string(),
+    };
+    let mut effects = vec![
+        PostEffect::new(state, &bright)?
+            .with_params(Vec4::new(threshold, 0.0, 0.0, 0.0))
+            .with_scale(0.5),
+    ];
+    effects.extend(blur(state, 1.0, 0.25)?);
+    effects
+        .push(PostEffect::new(state, &composite)?.with_params(Vec4::new(intensity, 0.0, 0.0, 0.0)));
+    Ok(effects)
+}
+
+/// Darkens corners of the image by `strength` starting from `radius`.
+///
+/// Radius is relative to the distance from the center to the corner.
+pub fn vignette(state: &Graphics, strength: f32, radius: f32) -> Result<PostEffect> {
+    let source = EffectSource {
+        source: String::new(),
+        fragment_source: "
+            let distance = length(tex_coord - 0.5) * 1.4142135;
+            color = vec4(color.rgb * (1.0 - params.x * smoothstep(params.y, 1.0, distance)), color.a);
+        "
+        .to_string(),
+    };
+    Ok(PostEffect::new(state, &source)?.with_params(Vec4::new(strength, radius, 0.0, 0.0)))
+}
+
+/// Maps colors through a 3D lookup table of `size` entries per channel.
+///
+/// The `lut` is a 2D texture of `size * size` by `size` texels, that consists of `size` square slices of increasing blue,
+/// where red increases to the right and green increases downwards in each slice.
+/// The table is indexed by color values as they are sampled from the scene, i.e. linear ones for sRGB targets.
+pub fn color_grading(state: &Graphics, lut: wgpu::TextureView, size: u32) -> Result<PostEffect> {
+    let source = EffectSource {
+        source: "
+            fn sample_lut(rgb: vec3<f32>, size: f32) -> vec3<f32> {
+                let index = clamp(rgb, vec3(0.0), vec3(1.0)) * (size - 1.0);
+                let slice = floor(index.b);
+                let next = min(slice + 1.0, size - 1.0);
+                let coord = (index.rg + 0.5) / vec2(size * size, size);
+                let a = sample_extra(coord + vec2(slice / size, 0.0)).rgb;
+                let b = sample_extra(coord + vec2(next / size, 0.0)).rgb;
+                return mix(a, b, index.b - slice);
+            }
+        "
+        .to_string(),
+        fragment_source: "color = vec4(sample_lut(color.rgb, params.x), color.a);".to_string(),
+    };
+    Ok(PostEffect::new(state, &source)?
+        .with_params(Vec4::new(size as f32, 0.0, 0.0, 0.0))
+        .with_texture(lut))
+}
+
+/// Imitates CRT monitor with screen `curvature` and darkening of every other line by `scanlines`.
+pub fn crt(state: &Graphics, curvature: f32, scanlines: f32) -> Result<PostEffect> {
+    let source = EffectSource {
+        source: String::new(),
+        fragment_source: "
+            let centered = tex_coord * 2.0 - 1.0;
+            let curved = (centered * (1.0 + params.x * centered.yx * centered.yx)) * 0.5 + 0.5;
+            if any(curved < vec2(0.0)) || any(curved > vec2(1.0)) {
+                color = vec4(0.0, 0.0, 0.0, 1.0);
+            } else {
+                let line = 0.5 + 0.5 * cos(floor(curved.y / texel_size.y) * 3.14159265);
+                color = vec4(sample_source(curved).rgb * mix(1.0, line, params.y), 1.0);
+            }
+        "
+        .to_string(),
+    };
+    Ok(PostEffect::new(state, &source)?.with_params(Vec4::new(curvature, scanlines, 0.0, 0.0)))
+}
@@ -1,42 +1,112 @@
-use anyhow::{Context as _, Result};
+use anyhow::{Result, bail};
 
-use crate::{Graphics, Surface, Target};
+use crate::{
+    FrameStats, Graphics, PendingImage, PooledTexture, Profiler, ReadPixel, Surface, Target,
+    pool::Recording,
+    readback::{DeferredReadback, Readback},
+};
 
 pub struct Frame<'a, 'b> {
     owner: &'b mut Surface<'a>,
     surface: wgpu::SurfaceTexture,
     view: wgpu::TextureView,
+    /// Texture that is rendered into in HDR mode before tonemapping.
+    hdr: Option<PooledTexture>,
     encoder: wgpu::CommandEncoder,
+    clear: Option<wgpu::Color>,
+    /// Depth and stencil buffers are cleared by the first render pass.
+    depth_clear: bool,
+    recording: Option<Recording>,
+    /// Screenshots that are copied when the frame is presented.
+    captures: Vec<DeferredReadback>,
+    device_recreated: bool,
 }
 
 impl<'a, 'b> Frame<'a, 'b> {
-    pub(crate) fn new(owner: &'b mut Surface<'a>) -> Result<Self> {
-        let surface = owner
-            .inner()
-            .get_current_texture()
-            .context("Failed to acquire next swap chain texture")?;
-        let view = surface
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+    pub(crate) fn new(owner: &'b mut Surface<'a>, surface: wgpu::SurfaceTexture) -> Self {
+        let view = surface.texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(owner.view_format()),
+            ..Default::default()
+        });
+        let hdr = owner.tonemapper().map(|_| {
+            let size = surface.texture.size();
+            owner.state().textures().get(
+                (size.width, size.height),
+                owner.state().format(),
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            )
+        });
+        let device_recreated = owner.take_device_recreated();
         let encoder = owner
             .state()
             .device()
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-        Ok(Frame {
+        Frame {
             owner,
             surface,
             view,
+            hdr,
             encoder,
-        })
+            clear: None,
+            depth_clear: true,
+            recording: None,
+            captures: Vec::new(),
+            device_recreated,
+        }
     }
 
-    pub fn present(self) {
+    /// Whether the lost device was recreated before this frame.
+    ///
+    /// Resources created on the previous device are invalid and must be recreated using the new [`Target::state`],
+    /// unless their owner does it by itself when it sees [`Graphics::replacement`] of the old state.
+    pub fn device_recreated(&self) -> bool {
+        self.device_recreated
+    }
+
+    /// Statistics collected since the previous frame was presented.
+    pub fn stats(&self) -> FrameStats {
+        self.owner.profiler().stats()
+    }
+
+    /// Captures contents of the frame as it is presented.
+    ///
+    /// Returned image resolves during one of the next frames without stalling the rendering,
+    /// so it should be polled with [`PendingImage::try_take`] or awaited in a separate task.
+    /// The image can be encoded, e.g. with `image.slice((.., ..)).encode("png")`.
+    pub fn capture<P: ReadPixel>(&mut self) -> Result<PendingImage<P>> {
+        let texture = &self.surface.texture;
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            bail!("Surface textures cannot be copied");
+        }
+        let (image, deferred) = Readback::new(self.owner.state(), texture)?.defer();
+        self.captures.push(deferred);
+        Ok(image)
+    }
+
+    /// Submits recorded commands, presents the frame and returns its statistics.
+    pub fn present(mut self) -> FrameStats {
+        self.flush();
+        let profiler = self.owner.profiler().clone();
+        if let (Some(tonemapper), Some(hdr)) = (self.owner.tonemapper(), &self.hdr) {
+            tonemapper.apply(&mut self.encoder, &profiler, hdr, &self.view);
+        }
+        for capture in &self.captures {
+            capture.record(&mut self.encoder, &self.surface.texture);
+        }
+        profiler.resolve(&mut self.encoder);
         self.owner
             .state()
             .queue()
             .submit(Some(self.encoder.finish()));
+        profiler.submitted();
+        self.owner.state().buffers().submitted();
+        self.owner.state().textures().submitted();
+        for capture in self.captures.drain(..) {
+            capture.map();
+        }
         self.surface.present();
+        profiler.take()
     }
 }
 
@@ -44,10 +114,30 @@ impl Target for Frame<'_, '_> {
     fn state(&self) -> &Graphics {
         self.owner.state()
     }
+    fn profiler(&self) -> &Profiler {
+        self.owner.profiler()
+    }
     fn view(&self) -> &wgpu::TextureView {
-        &self.view
+        match &self.hdr {
+            Some(hdr) => hdr.view(),
+            None => &self.view,
+        }
+    }
+    fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        self.owner.multisample_view()
+    }
+    fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.owner.depth_view()
     }
     fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        self.recording
+            .get_or_insert_with(|| self.owner.state().buffers().record());
         &mut self.encoder
     }
+    fn pending_clear(&mut self) -> &mut Option<wgpu::Color> {
+        &mut self.clear
+    }
+    fn pending_depth_clear(&mut self) -> &mut bool {
+        &mut self.depth_clear
+    }
 }
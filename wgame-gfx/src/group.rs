@@ -0,0 +1,179 @@
+use glam::Affine3A;
+use rgb::Rgba;
+
+use crate::{
+    Context, Instance, InstanceVisitor, Object, Ordered,
+    modifiers::{Colorable, Transformable},
+    types::{Color, Transform, color},
+};
+
+/// Object that can be a child of a [`Group`].
+pub trait Node: Object {
+    /// Visits instances of the object transformed by `xform` and multiplied by `color`.
+    fn for_each_transformed<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    );
+}
+
+impl<T> Node for T
+where
+    T: Object + Instance<Context = <T as Object>::Context> + Transformable + Colorable,
+{
+    fn for_each_transformed<V: InstanceVisitor<<T as Object>::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    ) {
+        let instance = self.transform(xform);
+        if color == color::WHITE.to_rgba() {
+            visitor.visit(&instance);
+        } else {
+            visitor.visit(&instance.multiply_color(color));
+        }
+    }
+}
+
+/// Collection of group children, e.g. a tuple of different objects or a vector of same ones.
+pub trait Children {
+    type Context: Context;
+    /// Visits instances of all children transformed by `xform` and multiplied by `color`.
+    fn for_each_child<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    );
+}
+
+/// Objects that are transformed and colored together.
+///
+/// Groups can be nested, transformation and color of the group apply to all its descendants.
+#[derive(Clone, Debug)]
+pub struct Group<T> {
+    pub children: T,
+    pub xform: Affine3A,
+    pub color: Rgba<f32>,
+    /// Order of all children, see [`Object::order`].
+    pub order: Option<i32>,
+}
+
+impl<T: Children> Group<T> {
+    pub fn new(children: T) -> Self {
+        Self {
+            children,
+            xform: Affine3A::IDENTITY,
+            color: color::WHITE.to_rgba(),
+            order: None,
+        }
+    }
+
+    pub fn with_order(self, order: i32) -> Self {
+        Self {
+            order: Some(order),
+            ..self
+        }
+    }
+}
+
+impl<T: Children> Object for Group<T> {
+    type Context = T::Context;
+    fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
+        self.for_each_transformed(Affine3A::IDENTITY, color::WHITE.to_rgba(), visitor);
+    }
+}
+
+impl<T: Children> Node for Group<T> {
+    fn for_each_transformed<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    ) {
+        let (xform, color) = (xform * self.xform, color.mul(self.color));
+        match self.order {
+            Some(order) => {
+                self.children
+                    .for_each_child(xform, color, &mut Ordered::new(visitor, order))
+            }
+            None => self.children.for_each_child(xform, color, visitor),
+        }
+    }
+}
+
+impl<T: Clone> Transformable for Group<T> {
+    fn transform<X: Transform>(&self, xform: X) -> Self {
+        Self {
+            xform: xform.to_affine3() * self.xform,
+            ..self.clone()
+        }
+    }
+}
+
+impl<T: Clone> Colorable for Group<T> {
+    fn multiply_color<C: Color>(&self, color: C) -> Self {
+        Self {
+            color: self.color.mul(color),
+            ..self.clone()
+        }
+    }
+}
+
+impl<N: Node> Children for Vec<N> {
+    type Context = N::Context;
+    fn for_each_child<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    ) {
+        for node in self {
+            node.for_each_transformed(xform, color, visitor);
+        }
+    }
+}
+
+impl<N: Node, const K: usize> Children for [N; K] {
+    type Context = N::Context;
+    fn for_each_child<V: InstanceVisitor<Self::Context>>(
+        &self,
+        xform: Affine3A,
+        color: Rgba<f32>,
+        visitor: &mut V,
+    ) {
+        for node in self {
+            node.for_each_transformed(xform, color, visitor);
+        }
+    }
+}
+
+macro_rules! impl_children_for_tuple {
+    ($First:ident $(, $Rest:ident)*) => {
+        impl<$First: Node $(, $Rest: Node<Context = $First::Context>)*> Children for ($First, $($Rest,)*) {
+            type Context = $First::Context;
+            #[allow(non_snake_case)]
+            fn for_each_child<V: InstanceVisitor<Self::Context>>(
+                &self,
+                xform: Affine3A,
+                color: Rgba<f32>,
+                visitor: &mut V,
+            ) {
+                let ($First, $($Rest,)*) = self;
+                $First.for_each_transformed(xform, color, visitor);
+                $($Rest.for_each_transformed(xform, color, visitor);)*
+            }
+        }
+    };
+}
+
+impl_children_for_tuple!(A);
+impl_children_for_tuple!(A, B);
+impl_children_for_tuple!(A, B, C);
+impl_children_for_tuple!(A, B, C, D);
+impl_children_for_tuple!(A, B, C, D, E);
+impl_children_for_tuple!(A, B, C, D, E, F);
+impl_children_for_tuple!(A, B, C, D, E, F, G);
+impl_children_for_tuple!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use glam::Vec4;
+
+use crate::{
+    ColorSpace, EffectSource, Graphics, PooledTexture, PostEffect, Profiler, post::EffectPasses,
+};
+
+/// Format of HDR render targets.
+pub(crate) const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Operator that maps HDR colors into displayable range.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Tonemapping {
+    #[default]
+    Reinhard,
+    /// Filmic curve approximating ACES.
+    Aces,
+    /// Only exposure is applied, values above `1.0` are kept on HDR surfaces and clamped otherwise.
+    None,
+}
+
+impl Tonemapping {
+    /// Source of the tonemapping effect.
+    ///
+    /// Tonemapping is done in linear space, so sRGB-encoded input is decoded if `decode_srgb` is set,
+    /// and the output is encoded if `encode_srgb` is set.
+    fn source(&self, decode_srgb: bool, encode_srgb: bool) -> EffectSource {
+        let fragment_source = match self {
+            Self::Reinhard => "color = vec4(hdr / (1.0 + hdr), color.a);",
+            Self::Aces => "color = vec4(aces(hdr), color.a);",
+            Self::None => "color = vec4(hdr, color.a);",
+        };
+        let decode_source = if decode_srgb {
+            "let linear = srgb_to_linear(max(color.rgb, vec3(0.0)));"
+        } else {
+            "let linear = color.rgb;"
+        };
+        let encode_source = if encode_srgb {
+            "color = vec4(linear_to_srgb(max(color.rgb, vec3(0.0))), color.a);"
+        } else {
+            ""
+        };
+        EffectSource {
+            source: "
+                fn aces(x: vec3<f32>) -> vec3<f32> {
+                    let a = 2.51;
+                    let b = 0.03;
+                    let c = 2.43;
+                    let d = 0.59;
+                    let e = 0.14;
+                    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3(0.0), vec3(1.0));
+                }
+            "
+            .to_string(),
+            fragment_source: format!(
+                "{decode_source}\nlet hdr = linear * params.x;\n{fragment_source}\n{encode_source}"
+            ),
+        }
+    }
+}
+
+/// HDR rendering settings.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct HdrConfig {
+    pub tonemapping: Tonemapping,
+    /// Multiplier applied to colors before tonemapping.
+    pub exposure: f32,
+}
+
+impl Default for HdrConfig {
+    fn default() -> Self {
+        Self {
+            tonemapping: Tonemapping::default(),
+            exposure: 1.0,
+        }
+    }
+}
+
+/// Converts HDR image into the output format using tonemapping operator.
+///
+/// With [`ColorSpace::Srgb`] the HDR image contains sRGB-encoded colors, they are decoded before tonemapping.
+/// Linear colors are written into float formats, e.g. scRGB surfaces, and encoded for other formats without sRGB variant.
+pub struct Tonemapper {
+    passes: EffectPasses,
+    format: wgpu::TextureFormat,
+    config: HdrConfig,
+    effect: PostEffect,
+}
+
+impl Tonemapper {
+    pub(crate) fn new(
+        state: &Graphics,
+        format: wgpu::TextureFormat,
+        config: HdrConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            passes: EffectPasses::new(state),
+            format,
+            config,
+            effect: Self::create_effect(state, format, config)?,
+        })
+    }
+
+    fn create_effect(
+        state: &Graphics,
+        format: wgpu::TextureFormat,
+        config: HdrConfig,
+    ) -> Result<PostEffect> {
+        // Colors are linear after decoding, so encoding is needed as in linear color space.
+        let source = config.tonemapping.source(
+            state.color_space() == ColorSpace::Srgb,
+            ColorSpace::Linear.encodes_in_shader(format),
+        );
+        Ok(
+            PostEffect::with_format(state, &source, format)?.with_params(Vec4::new(
+                config.exposure,
+                0.0,
+                0.0,
+                0.0,
+            )),
+        )
+    }
+
+    pub fn config(&self) -> HdrConfig {
+        self.config
+    }
+    /// Changes tonemapping settings, pipeline is recreated only if the operator is changed.
+    pub fn set_config(&mut self, config: HdrConfig) -> Result<()> {
+        if config.tonemapping != self.config.tonemapping {
+            self.effect = Self::create_effect(self.passes.state(), self.format, config)?;
+        }
+        self.effect
+            .set_params(Vec4::new(config.exposure, 0.0, 0.0, 0.0));
+        self.config = config;
+        Ok(())
+    }
+
+    /// Records tonemapping of the `hdr` texture into the `output` view.
+    pub(crate) fn apply(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        profiler: &Profiler,
+        hdr: &PooledTexture,
+        output: &wgpu::TextureView,
+    ) {
+        (self.passes).pass(encoder, profiler, &self.effect, (hdr, hdr), (output, None));
+    }
+}
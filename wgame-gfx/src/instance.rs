@@ -1,6 +1,6 @@
 use std::{any::Any, rc::Rc};
 
-use crate::{AnyResource, Context, Renderer, Resource};
+use crate::{AnyResource, Bounds, ClipRect, Context, Renderer, Resource};
 
 /// Single instance to draw.
 pub trait Instance {
@@ -11,6 +11,13 @@ pub trait Instance {
     fn resource(&self) -> Self::Resource;
     fn new_storage(&self) -> Self::Storage;
     fn store(&self, storage: &mut Self::Storage);
+
+    /// Bounding box of the instance in world space.
+    ///
+    /// `None` means that bounds are unknown and the instance is never culled.
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
 }
 
 impl<T: Instance> Instance for &T {
@@ -27,6 +34,9 @@ impl<T: Instance> Instance for &T {
     fn store(&self, storage: &mut Self::Storage) {
         (**self).store(storage)
     }
+    fn bounds(&self) -> Option<Bounds> {
+        (**self).bounds()
+    }
 }
 
 pub trait Storage: Any {
@@ -70,4 +80,10 @@ impl<S: Storage + ?Sized> Renderer<S::Context> for S {
     fn render(&self, ctx: &S::Context, pass: &mut wgpu::RenderPass<'_>) {
         self.bake().render(ctx, pass);
     }
+    fn scissor(&self) -> Option<ClipRect> {
+        self.resource().scissor()
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.resource().stencil_reference()
+    }
 }
@@ -5,37 +5,70 @@
 #![forbid(unsafe_code)]
 
 mod auto;
+mod bounds;
+mod cache;
 mod camera;
+mod clip;
+mod compute;
+pub mod effects;
 mod frame;
+mod group;
+mod hdr;
 mod instance;
+mod mask;
 pub mod modifiers;
 mod object;
+mod offscreen;
 mod order;
+mod pipeline;
+mod pool;
+mod post;
+mod profiler;
+mod readback;
 mod renderer;
 mod resource;
+mod retained;
 mod scene;
 mod state;
 mod surface;
 mod target;
+#[cfg(test)]
+mod tests;
 pub mod types;
 pub mod utils;
+mod viewport;
 
 pub use self::{
     auto::AutoScene,
+    bounds::Bounds,
+    cache::{PipelineKey, VertexBufferKey},
     camera::Camera,
+    clip::{ClipRect, Clipped, ClippedRenderer},
+    compute::{ComputePipeline, StorageBuffer},
     frame::Frame,
+    group::{Children, Group, Node},
+    hdr::{HdrConfig, Tonemapper, Tonemapping},
     instance::{AnyStorage, Instance, Storage},
+    mask::{Masked, Stenciled, StenciledRenderer},
     object::{InstanceVisitor, Object},
+    offscreen::TextureTarget,
     order::Ordered,
+    pipeline::{BlendMode, PipelineState, PipelineVariants, StencilMode},
+    pool::{BufferPool, PooledBuffer, PooledTexture, TexturePool},
+    post::{EffectSource, PostEffect, PostProcess, PostTarget},
+    profiler::{FrameStats, Profiler},
+    readback::{PendingImage, ReadPixel},
     renderer::{Context, Renderer},
     resource::{AnyResource, Resource},
-    scene::Scene,
+    retained::{ObjectId, RetainedScene},
+    scene::{Batching, Scene},
     state::Graphics,
     surface::Surface,
     target::Target,
+    viewport::Viewport,
 };
 pub use anyhow::Error;
-pub use wgpu::PresentMode;
+pub use wgpu::{PresentMode, TextureFormat};
 
 /// Commonly used types and traits.
 pub mod prelude {
@@ -46,12 +79,63 @@ pub mod prelude {
 #[derive(Clone, Debug)]
 pub struct Config {
     pub present_mode: wgpu::PresentMode,
+    /// Use fallback (software) adapter, e.g. for rendering in CI.
+    pub force_fallback_adapter: bool,
+    /// Number of samples per pixel used for multisample anti-aliasing.
+    ///
+    /// `1` disables MSAA. Unsupported values fall back to `1`.
+    pub msaa_samples: u32,
+    /// Format of depth (and stencil) buffer attached to render targets.
+    ///
+    /// `None` means that targets have no depth buffer.
+    pub depth_format: Option<wgpu::TextureFormat>,
+    pub color_space: ColorSpace,
+    /// Render window surface into HDR texture, which is tonemapped into the surface when presented.
+    ///
+    /// HDR surface format is used if supported. Offscreen targets are not affected,
+    /// they can be created in `Rgba16Float` format directly.
+    pub hdr: Option<HdrConfig>,
+    /// Measure duration of render passes on GPU, if `TIMESTAMP_QUERY` feature is supported.
+    pub profiling: bool,
+}
+
+/// Color space in which rendering and blending are performed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum ColorSpace {
+    /// Colors are sRGB-encoded and are written to the target as is, so blending is done on encoded values.
+    #[default]
+    Srgb,
+    /// Colors are linear, blending is done in linear space and results are encoded into sRGB when written.
+    ///
+    /// Target format is the sRGB variant of the requested or supported one.
+    /// If the format has no sRGB variant (e.g. `Rgb10a2Unorm`), colors are encoded by shaders,
+    /// so blending is done on encoded values. Float formats store linear values as is.
+    /// Images that are stored in sRGB, e.g. PNG, are decoded to linear space when loaded.
+    Linear,
+}
+
+impl ColorSpace {
+    /// Whether shaders writing into `format` must encode colors into sRGB themselves.
+    pub(crate) fn encodes_in_shader(self, format: wgpu::TextureFormat) -> bool {
+        use wgpu::TextureFormat::*;
+        let is_float = matches!(
+            format,
+            R16Float | Rg16Float | Rgba16Float | R32Float | Rg32Float | Rgba32Float | Rg11b10Ufloat
+        );
+        self == Self::Linear && !format.is_srgb() && !is_float
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             present_mode: wgpu::PresentMode::AutoVsync,
+            force_fallback_adapter: false,
+            msaa_samples: 1,
+            depth_format: None,
+            color_space: ColorSpace::default(),
+            hdr: None,
+            profiling: false,
         }
     }
 }
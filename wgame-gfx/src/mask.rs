@@ -0,0 +1,147 @@
+use crate::{
+    Bounds, ClipRect, Context, Instance, InstanceVisitor, Object, Renderer, Resource, Storage,
+    modifiers::Pipelined,
+    pipeline::{PipelineState, StencilMode},
+};
+
+/// Object that is drawn only inside of the mask shape.
+///
+/// Mask is rendered into the stencil buffer with the `reference` value, then content is drawn
+/// only where the stencil buffer contains that value.
+///
+/// Requires depth buffer format with stencil aspect, otherwise content is drawn unmasked and a warning is logged.
+/// Stencil buffer is reset by the first render pass into the target and when the target is cleared,
+/// so overlapping masked objects drawn into the same frame should use different references.
+#[derive(Clone, Debug)]
+pub struct Masked<M, T> {
+    pub mask: M,
+    pub content: T,
+    pub reference: u32,
+}
+
+impl<M, T> Masked<M, T> {
+    pub fn new(mask: M, content: T) -> Self {
+        Self {
+            mask,
+            content,
+            reference: 1,
+        }
+    }
+
+    pub fn reference(self, reference: u32) -> Self {
+        Self { reference, ..self }
+    }
+}
+
+fn with_stencil<T: Pipelined>(object: &T, stencil: StencilMode) -> T {
+    object.with_pipeline_state(PipelineState {
+        stencil,
+        ..object.pipeline_state()
+    })
+}
+
+impl<M, T> Object for Masked<M, T>
+where
+    M: Object + Pipelined,
+    T: Object<Context = M::Context> + Pipelined,
+{
+    type Context = M::Context;
+    fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
+        let mut visitor = Stenciled::new(visitor, self.reference);
+        // Mask instances are visited first, so that their batches are drawn before the content.
+        with_stencil(&self.mask, StencilMode::Write).for_each_instance(&mut visitor);
+        with_stencil(&self.content, StencilMode::Test).for_each_instance(&mut visitor);
+    }
+}
+
+/// Object which instances are drawn with the given stencil reference.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Stenciled<T: ?Sized> {
+    pub reference: u32,
+    pub inner: T,
+}
+
+impl<T> Stenciled<T> {
+    pub fn new(inner: T, reference: u32) -> Self {
+        Self { inner, reference }
+    }
+}
+
+impl<R: Resource> Resource for Stenciled<R> {
+    fn order(&self) -> impl Iterator<Item = i32> {
+        self.inner.order()
+    }
+    fn scissor(&self) -> Option<ClipRect> {
+        self.inner.scissor()
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.reference
+    }
+}
+
+/// Renderer of [`Stenciled`] storage.
+pub struct StenciledRenderer<R> {
+    reference: u32,
+    inner: R,
+}
+
+impl<C: Context, R: Renderer<C>> Renderer<C> for StenciledRenderer<R> {
+    fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>) {
+        self.inner.render(ctx, pass);
+    }
+    fn scissor(&self) -> Option<ClipRect> {
+        self.inner.scissor()
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.reference
+    }
+}
+
+impl<S: Storage> Storage for Stenciled<S> {
+    type Context = S::Context;
+    type Resource = Stenciled<S::Resource>;
+    type Renderer = StenciledRenderer<S::Renderer>;
+
+    fn resource(&self) -> Self::Resource {
+        Stenciled::new(self.inner.resource(), self.reference)
+    }
+    fn bake(&self) -> Self::Renderer {
+        StenciledRenderer {
+            reference: self.reference,
+            inner: self.inner.bake(),
+        }
+    }
+}
+
+impl<T: Instance + ?Sized> Instance for Stenciled<T> {
+    type Context = T::Context;
+    type Resource = Stenciled<T::Resource>;
+    type Storage = Stenciled<T::Storage>;
+
+    fn resource(&self) -> Self::Resource {
+        Stenciled::new(self.inner.resource(), self.reference)
+    }
+    fn new_storage(&self) -> Self::Storage {
+        Stenciled::new(self.inner.new_storage(), self.reference)
+    }
+    fn store(&self, storage: &mut Self::Storage) {
+        self.inner.store(&mut storage.inner);
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        self.inner.bounds()
+    }
+}
+
+impl<T: Object + ?Sized> Object for Stenciled<T> {
+    type Context = T::Context;
+    fn for_each_instance<V: InstanceVisitor<Self::Context>>(&self, visitor: &mut V) {
+        self.inner
+            .for_each_instance(&mut Stenciled::new(visitor, self.reference));
+    }
+}
+
+impl<C: Context, V: InstanceVisitor<C>> InstanceVisitor<C> for Stenciled<V> {
+    fn visit<T: Instance<Context = C>>(&mut self, instance: &T) {
+        self.inner.visit(&Stenciled::new(instance, self.reference));
+    }
+}
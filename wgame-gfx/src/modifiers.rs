@@ -1,6 +1,9 @@
 use glam::{Affine3A, Vec3};
 
-use crate::types::{Color, Position, Transform};
+use crate::{
+    BlendMode, PipelineState,
+    types::{Color, Position, Transform},
+};
 
 pub trait Transformable: Sized {
     fn transform<X: Transform>(&self, xform: X) -> Self;
@@ -17,8 +20,38 @@ pub trait Colorable: Sized {
     fn multiply_color<C: Color>(&self, color: C) -> Self;
 }
 
+/// Object which pipeline state can be changed.
+pub trait Pipelined: Sized {
+    fn pipeline_state(&self) -> PipelineState;
+    fn with_pipeline_state(&self, state: PipelineState) -> Self;
+
+    /// Enables depth testing, so that the object is ordered by its depth rather than by drawing order.
+    fn depth_test(&self, enabled: bool) -> Self {
+        let mut state = self.pipeline_state();
+        state.depth_test = enabled;
+        self.with_pipeline_state(state)
+    }
+
+    /// Sets the way the object is blended with the target.
+    fn blend(&self, mode: BlendMode) -> Self {
+        let mut state = self.pipeline_state();
+        state.blend = mode;
+        self.with_pipeline_state(state)
+    }
+}
+
 #[macro_export]
 macro_rules! impl_transformable {
+    ($self:ident<$($param:ident),+>, $field:ident) => {
+        impl<$($param),+> $crate::modifiers::Transformable for $self<$($param),+> {
+            fn transform<X: $crate::types::Transform>(&self, xform: X) -> Self {
+                Self {
+                    $field: xform.to_affine3() * self.$field,
+                    ..self.clone()
+                }
+            }
+        }
+    };
     ($self:ty, $field:ident) => {
         impl $crate::modifiers::Transformable for $self {
             fn transform<X: $crate::types::Transform>(&self, xform: X) -> Self {
@@ -33,6 +66,16 @@ macro_rules! impl_transformable {
 
 #[macro_export]
 macro_rules! delegate_transformable {
+    ($self:ident<$($param:ident),+>, $inner:ident) => {
+        impl<$($param),+> $crate::modifiers::Transformable for $self<$($param),+> {
+            fn transform<X: $crate::types::Transform>(&self, xform: X) -> Self {
+                Self {
+                    $inner: $crate::modifiers::Transformable::transform(&self.$inner, xform),
+                    ..self.clone()
+                }
+            }
+        }
+    };
     ($self:ty, $inner:ident) => {
         impl $crate::modifiers::Transformable for $self {
             fn transform<X: $crate::types::Transform>(&self, xform: X) -> Self {
@@ -44,3 +87,63 @@ macro_rules! delegate_transformable {
         }
     };
 }
+
+#[macro_export]
+macro_rules! impl_pipelined {
+    ($self:ident<$($param:ident),+>, $field:ident) => {
+        impl<$($param),+> $crate::modifiers::Pipelined for $self<$($param),+> {
+            fn pipeline_state(&self) -> $crate::PipelineState {
+                self.$field
+            }
+            fn with_pipeline_state(&self, state: $crate::PipelineState) -> Self {
+                Self {
+                    $field: state,
+                    ..self.clone()
+                }
+            }
+        }
+    };
+    ($self:ty, $field:ident) => {
+        impl $crate::modifiers::Pipelined for $self {
+            fn pipeline_state(&self) -> $crate::PipelineState {
+                self.$field
+            }
+            fn with_pipeline_state(&self, state: $crate::PipelineState) -> Self {
+                Self {
+                    $field: state,
+                    ..self.clone()
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! delegate_pipelined {
+    ($self:ident<$($param:ident),+>, $inner:ident) => {
+        impl<$($param),+> $crate::modifiers::Pipelined for $self<$($param),+> {
+            fn pipeline_state(&self) -> $crate::PipelineState {
+                $crate::modifiers::Pipelined::pipeline_state(&self.$inner)
+            }
+            fn with_pipeline_state(&self, state: $crate::PipelineState) -> Self {
+                Self {
+                    $inner: $crate::modifiers::Pipelined::with_pipeline_state(&self.$inner, state),
+                    ..self.clone()
+                }
+            }
+        }
+    };
+    ($self:ty, $inner:ident) => {
+        impl $crate::modifiers::Pipelined for $self {
+            fn pipeline_state(&self) -> $crate::PipelineState {
+                $crate::modifiers::Pipelined::pipeline_state(&self.$inner)
+            }
+            fn with_pipeline_state(&self, state: $crate::PipelineState) -> Self {
+                Self {
+                    $inner: $crate::modifiers::Pipelined::with_pipeline_state(&self.$inner, state),
+                    ..self.clone()
+                }
+            }
+        }
+    };
+}
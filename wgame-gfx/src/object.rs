@@ -1,4 +1,4 @@
-use crate::{Context, Instance, Ordered};
+use crate::{ClipRect, Clipped, Context, Instance, Masked, Ordered, modifiers::Pipelined};
 
 pub trait InstanceVisitor<C: Context> {
     fn visit<T: Instance<Context = C>>(&mut self, instance: &T);
@@ -20,6 +20,22 @@ pub trait Object {
     {
         Ordered::new(self.clone(), order)
     }
+
+    /// Draws the object only inside of `rect`.
+    fn clip(&self, rect: ClipRect) -> Clipped<Self>
+    where
+        Self: Clone,
+    {
+        Clipped::new(self.clone(), rect)
+    }
+
+    /// Draws the object only inside of the `mask` shape.
+    fn mask<M: Object<Context = Self::Context> + Pipelined>(&self, mask: M) -> Masked<M, Self>
+    where
+        Self: Clone + Pipelined,
+    {
+        Masked::new(mask, self.clone())
+    }
 }
 
 impl<T: Object> Object for &T {
@@ -31,6 +47,20 @@ impl<T: Object> Object for &T {
 
 #[macro_export]
 macro_rules! impl_object_for_instance {
+    ($Self:ident<$($param:ident),+>) => {
+        impl<$($param),+> Object for $Self<$($param),+>
+        where
+            Self: $crate::Instance,
+        {
+            type Context = <Self as $crate::Instance>::Context;
+            fn for_each_instance<V: $crate::InstanceVisitor<Self::Context>>(
+                &self,
+                visitor: &mut V,
+            ) {
+                visitor.visit(self);
+            }
+        }
+    };
     ($Self:ty) => {
         impl Object for $Self {
             type Context = <Self as $crate::Instance>::Context;
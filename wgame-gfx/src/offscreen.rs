@@ -0,0 +1,132 @@
+use anyhow::Result;
+use wgame_image::Image;
+
+use crate::{
+    FrameStats, Graphics, Profiler, Target,
+    pool::Recording,
+    readback::{ReadPixel, Readback},
+};
+
+/// Render target that owns its texture.
+///
+/// Can be used without a window, and its contents can be read back into an image.
+pub struct TextureTarget {
+    state: Graphics,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    multisample: Option<wgpu::TextureView>,
+    depth: Option<wgpu::TextureView>,
+    encoder: wgpu::CommandEncoder,
+    clear: Option<wgpu::Color>,
+    /// Depth and stencil buffers are cleared by the first render pass.
+    depth_clear: bool,
+    recording: Option<Recording>,
+    profiler: Profiler,
+}
+
+impl TextureTarget {
+    /// Creates a target of the given size in the graphics' format.
+    pub fn new(state: &Graphics, size: (u32, u32)) -> Self {
+        let texture = state.device().create_texture(&wgpu::TextureDescriptor {
+            label: Some("texture_target"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: state.format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            encoder: Self::create_encoder(state),
+            state: state.clone(),
+            texture,
+            view,
+            multisample: state.create_multisample_view(size),
+            depth: state.create_depth_view(size),
+            clear: None,
+            depth_clear: true,
+            recording: None,
+            profiler: state.create_profiler(),
+        }
+    }
+
+    fn create_encoder(state: &Graphics) -> wgpu::CommandEncoder {
+        state
+            .device()
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None })
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// Statistics collected since the previous submission.
+    pub fn stats(&self) -> FrameStats {
+        self.profiler.stats()
+    }
+
+    /// Submits all commands recorded so far and returns their statistics.
+    pub fn submit(&mut self) -> FrameStats {
+        self.flush();
+        self.profiler.resolve(&mut self.encoder);
+        let encoder = std::mem::replace(&mut self.encoder, Self::create_encoder(&self.state));
+        self.state.queue().submit(Some(encoder.finish()));
+        self.profiler.submitted();
+        self.state.buffers().submitted();
+        self.state.textures().submitted();
+        self.recording = None;
+        self.profiler.take()
+    }
+
+    /// Submits recorded commands and reads the target contents into an image.
+    ///
+    /// On native platforms this blocks until the GPU finishes rendering.
+    pub async fn read<P: ReadPixel>(&mut self) -> Result<Image<P>> {
+        self.flush();
+        let readback = Readback::record(&self.state, &mut self.encoder, &self.texture)?;
+        self.submit();
+        let image = readback.map::<P>();
+        self.state
+            .device()
+            .poll(wgpu::PollType::wait_indefinitely())?;
+        image.await
+    }
+}
+
+impl Target for TextureTarget {
+    fn state(&self) -> &Graphics {
+        &self.state
+    }
+    fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+    fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+    fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        self.multisample.as_ref()
+    }
+    fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth.as_ref()
+    }
+    fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        self.recording
+            .get_or_insert_with(|| self.state.buffers().record());
+        &mut self.encoder
+    }
+    fn pending_clear(&mut self) -> &mut Option<wgpu::Color> {
+        &mut self.clear
+    }
+    fn pending_depth_clear(&mut self) -> &mut bool {
+        &mut self.depth_clear
+    }
+}
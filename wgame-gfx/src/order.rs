@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::{Context, Instance, InstanceVisitor, Object, Resource, Storage};
+use crate::{Bounds, ClipRect, Context, Instance, InstanceVisitor, Object, Resource, Storage};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Ordered<T: ?Sized> {
@@ -18,6 +18,12 @@ impl<R: Resource> Resource for Ordered<R> {
     fn order(&self) -> impl Iterator<Item = i32> {
         iter::once(self.order).chain(self.inner.order())
     }
+    fn scissor(&self) -> Option<ClipRect> {
+        self.inner.scissor()
+    }
+    fn stencil_reference(&self) -> u32 {
+        self.inner.stencil_reference()
+    }
 }
 
 impl<S: Storage> Storage for Ordered<S> {
@@ -47,6 +53,9 @@ impl<T: Instance + ?Sized> Instance for Ordered<T> {
     fn store(&self, storage: &mut Self::Storage) {
         self.inner.store(&mut storage.inner);
     }
+    fn bounds(&self) -> Option<Bounds> {
+        self.inner.bounds()
+    }
 }
 
 impl<T: Object + ?Sized> Object for Ordered<T> {
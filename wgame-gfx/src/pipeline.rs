@@ -0,0 +1,166 @@
+use std::{cell::RefCell, sync::Once};
+
+use hashbrown::HashMap;
+
+use crate::Graphics;
+
+/// How objects interact with the stencil buffer.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum StencilMode {
+    /// Stencil buffer is not used.
+    #[default]
+    Disabled,
+    /// Object writes the stencil reference into the stencil buffer instead of drawing colors.
+    Write,
+    /// Object is drawn only where the stencil buffer contains the stencil reference.
+    Test,
+}
+
+/// How object colors are combined with colors already present in the target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum BlendMode {
+    /// Conventional alpha blending of non-premultiplied colors.
+    #[default]
+    Alpha,
+    /// Alpha blending of colors that are already multiplied by alpha.
+    PremultipliedAlpha,
+    /// Adds color multiplied by alpha to the target, e.g. for lights and particles.
+    Additive,
+    /// Multiplies target by the color, weighted by alpha.
+    Multiply,
+    /// Inverse of multiplying inverted colors weighted by alpha, brightens the target.
+    Screen,
+    /// Replaces target color ignoring alpha.
+    Opaque,
+}
+
+impl BlendMode {
+    /// Whether the blend state expects colors to be premultiplied by alpha in the shader.
+    ///
+    /// Shaders of such pipelines get `premultiply_alpha` override constant set to `true`,
+    /// see [`PipelineKey`](crate::PipelineKey).
+    pub fn premultiplies_in_shader(&self) -> bool {
+        matches!(self, Self::Multiply | Self::Screen)
+    }
+
+    pub fn blend_state(&self) -> Option<wgpu::BlendState> {
+        let color = |src_factor, dst_factor| wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor,
+                dst_factor,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent::OVER,
+        };
+        match self {
+            Self::Alpha => Some(wgpu::BlendState::ALPHA_BLENDING),
+            Self::PremultipliedAlpha => Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            Self::Additive => Some(color(wgpu::BlendFactor::SrcAlpha, wgpu::BlendFactor::One)),
+            // Source colors are premultiplied, so transparent pixels keep the target unchanged.
+            Self::Multiply => Some(color(
+                wgpu::BlendFactor::Dst,
+                wgpu::BlendFactor::OneMinusSrcAlpha,
+            )),
+            Self::Screen => Some(color(
+                wgpu::BlendFactor::One,
+                wgpu::BlendFactor::OneMinusSrc,
+            )),
+            Self::Opaque => None,
+        }
+    }
+}
+
+/// Fixed-function render pipeline state that can be chosen per object.
+///
+/// Objects with different states are drawn in different batches.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct PipelineState {
+    /// Test fragments against the depth buffer and write their depth into it.
+    ///
+    /// Requires depth buffer to be enabled in [`Config`](crate::Config), otherwise it has no effect.
+    pub depth_test: bool,
+    /// Requires depth buffer format with stencil aspect.
+    pub stencil: StencilMode,
+    pub blend: BlendMode,
+}
+
+impl PipelineState {
+    /// Depth-stencil state of pipelines that render into targets of the `state`.
+    pub fn depth_stencil(&self, state: &Graphics) -> Option<wgpu::DepthStencilState> {
+        if self.stencil != StencilMode::Disabled
+            && !state
+                .depth_format()
+                .is_some_and(|format| format.has_stencil_aspect())
+        {
+            static WARNING: Once = Once::new();
+            WARNING.call_once(|| {
+                log::warn!(
+                    "Depth buffer format has no stencil aspect, masked content is drawn unmasked"
+                )
+            });
+        }
+        let format = state.depth_format()?;
+        let stencil_face = match self.stencil {
+            StencilMode::Disabled => wgpu::StencilFaceState::IGNORE,
+            StencilMode::Write => wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Always,
+                fail_op: wgpu::StencilOperation::Keep,
+                depth_fail_op: wgpu::StencilOperation::Keep,
+                pass_op: wgpu::StencilOperation::Replace,
+            },
+            StencilMode::Test => wgpu::StencilFaceState {
+                compare: wgpu::CompareFunction::Equal,
+                ..wgpu::StencilFaceState::IGNORE
+            },
+        };
+        Some(wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: self.depth_test && self.stencil != StencilMode::Write,
+            depth_compare: if self.depth_test {
+                wgpu::CompareFunction::LessEqual
+            } else {
+                wgpu::CompareFunction::Always
+            },
+            stencil: if format.has_stencil_aspect() {
+                wgpu::StencilState {
+                    front: stencil_face,
+                    back: stencil_face,
+                    read_mask: !0,
+                    write_mask: !0,
+                }
+            } else {
+                wgpu::StencilState::default()
+            },
+            bias: wgpu::DepthBiasState::default(),
+        })
+    }
+
+    /// Color channels that pipeline writes.
+    pub fn color_writes(&self) -> wgpu::ColorWrites {
+        match self.stencil {
+            StencilMode::Write => wgpu::ColorWrites::empty(),
+            _ => wgpu::ColorWrites::ALL,
+        }
+    }
+}
+
+/// Lazily created variants of a render pipeline for different pipeline states.
+#[derive(Default)]
+pub struct PipelineVariants {
+    variants: RefCell<HashMap<PipelineState, wgpu::RenderPipeline>>,
+}
+
+impl PipelineVariants {
+    /// Returns variant for `state`, creating it with `create` if needed.
+    pub fn get_or_create(
+        &self,
+        state: PipelineState,
+        create: impl FnOnce() -> wgpu::RenderPipeline,
+    ) -> wgpu::RenderPipeline {
+        self.variants
+            .borrow_mut()
+            .entry(state)
+            .or_insert_with(create)
+            .clone()
+    }
+}
@@ -0,0 +1,441 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZero,
+    ops::Range,
+    ptr,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use crate::Profiler;
+
+/// Smallest size of pooled buffer.
+const MIN_BUFFER_SIZE: u64 = 256;
+/// Number of submissions after which a free buffer or texture is dropped.
+const MAX_IDLE_SUBMISSIONS: u64 = 8;
+
+/// Pool of GPU buffers that are reused across frames.
+///
+/// Buffers are grouped by usage and power-of-two size classes and are filled using [`wgpu::Queue::write_buffer`].
+/// A buffer returns to the pool when all its handles are dropped and all commands that might use it are submitted.
+/// Buffers that stay free for several submissions of frames or texture targets are dropped.
+#[derive(Clone)]
+pub struct BufferPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    profiler: Profiler,
+    slots: Mutex<Vec<Arc<Slot>>>,
+    /// Number of command encoders that are being recorded but not submitted yet.
+    recording: AtomicUsize,
+    /// Number of submissions so far.
+    submission: AtomicU64,
+}
+
+struct Slot {
+    buffer: wgpu::Buffer,
+    usage: wgpu::BufferUsages,
+    in_use: AtomicBool,
+    /// Submission during which the buffer was used last time.
+    last_used: AtomicU64,
+    bind_group: Mutex<Option<CachedBindGroup>>,
+}
+
+/// Bind group of the buffer with the layout and bound ranges it was created for.
+struct CachedBindGroup {
+    layout: wgpu::BindGroupLayout,
+    entries: Vec<(u32, Range<u64>)>,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Buffer borrowed from [`BufferPool`].
+#[derive(Clone)]
+pub struct PooledBuffer {
+    slot: Arc<Slot>,
+    size: u64,
+}
+
+/// Marks that commands which may use pooled buffers are being recorded.
+///
+/// Released buffers are not reused until all recordings are dropped.
+pub(crate) struct Recording {
+    pool: BufferPool,
+}
+
+impl BufferPool {
+    pub(crate) fn new(device: &wgpu::Device, queue: &wgpu::Queue, profiler: &Profiler) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                device: device.clone(),
+                queue: queue.clone(),
+                profiler: profiler.clone(),
+                slots: Mutex::default(),
+                recording: AtomicUsize::new(0),
+                submission: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Takes a free buffer of the given `usage` and writes `contents` into it.
+    ///
+    /// `COPY_DST` usage is added automatically.
+    pub fn write(&self, usage: wgpu::BufferUsages, contents: &[u8]) -> PooledBuffer {
+        let buffer = self.alloc(usage, contents.len() as u64);
+        self.inner.profiler.record_upload(contents.len() as u64);
+        if contents
+            .len()
+            .is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize)
+        {
+            self.inner.queue.write_buffer(buffer.buffer(), 0, contents);
+        } else {
+            let mut padded = contents.to_vec();
+            padded.resize(
+                contents
+                    .len()
+                    .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize),
+                0,
+            );
+            self.inner.queue.write_buffer(buffer.buffer(), 0, &padded);
+        }
+        buffer
+    }
+
+    /// Takes a free buffer of the given `usage` that is at least `size` bytes long.
+    pub fn alloc(&self, usage: wgpu::BufferUsages, size: u64) -> PooledBuffer {
+        let usage = usage | wgpu::BufferUsages::COPY_DST;
+        let capacity = size.max(MIN_BUFFER_SIZE).next_power_of_two();
+        let mut slots = self.inner.slots.lock().unwrap();
+        let slot = match slots.iter().find(|slot| {
+            !slot.in_use.load(Ordering::Relaxed)
+                && slot.usage == usage
+                && slot.buffer.size() == capacity
+        }) {
+            Some(slot) => slot.clone(),
+            None => {
+                let slot = Arc::new(Slot {
+                    buffer: self.inner.device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("pooled"),
+                        size: capacity,
+                        usage,
+                        mapped_at_creation: false,
+                    }),
+                    usage,
+                    in_use: AtomicBool::new(false),
+                    last_used: AtomicU64::new(0),
+                    bind_group: Mutex::default(),
+                });
+                slots.push(slot.clone());
+                slot
+            }
+        };
+        slot.in_use.store(true, Ordering::Relaxed);
+        (slot.last_used).store(
+            self.inner.submission.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        PooledBuffer { slot, size }
+    }
+
+    /// Number of buffers allocated by the pool.
+    pub fn len(&self) -> usize {
+        self.inner.slots.lock().unwrap().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops buffers that were not used during the last [`MAX_IDLE_SUBMISSIONS`], called after commands are submitted.
+    pub(crate) fn submitted(&self) {
+        let submission = self.inner.submission.fetch_add(1, Ordering::Relaxed) + 1;
+        self.inner.slots.lock().unwrap().retain(|slot| {
+            if slot.in_use.load(Ordering::Relaxed) {
+                slot.last_used.store(submission, Ordering::Relaxed);
+            }
+            submission - slot.last_used.load(Ordering::Relaxed) <= MAX_IDLE_SUBMISSIONS
+        });
+    }
+
+    pub(crate) fn record(&self) -> Recording {
+        self.inner.recording.fetch_add(1, Ordering::Relaxed);
+        Recording { pool: self.clone() }
+    }
+
+    /// Returns buffers that have no handles left back to the pool.
+    fn recycle(&self) {
+        for slot in self.inner.slots.lock().unwrap().iter() {
+            if Arc::strong_count(slot) == 1 {
+                slot.in_use.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        if self.pool.inner.recording.fetch_sub(1, Ordering::Relaxed) == 1 {
+            self.pool.recycle();
+        }
+    }
+}
+
+impl PooledBuffer {
+    /// Underlying buffer, it may be larger than requested.
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.slot.buffer
+    }
+    /// Requested size of the buffer.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    /// Slice of the buffer with the requested size.
+    pub fn slice(&self) -> wgpu::BufferSlice<'_> {
+        self.slot.buffer.slice(..self.size)
+    }
+
+    /// Returns bind group of `layout` that binds ranges of this buffer, given as binding index and byte range.
+    ///
+    /// The bind group is created only once per underlying buffer while the `layout` and `entries` are the same.
+    pub fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        entries: &[(u32, Range<u64>)],
+    ) -> wgpu::BindGroup {
+        let mut cache = self.slot.bind_group.lock().unwrap();
+        if let Some(cached) = &*cache
+            && cached.layout == *layout
+            && cached.entries == entries
+        {
+            return cached.bind_group.clone();
+        }
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("pooled"),
+            layout,
+            entries: &entries
+                .iter()
+                .map(|(binding, range)| wgpu::BindGroupEntry {
+                    binding: *binding,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.slot.buffer,
+                        offset: range.start,
+                        size: NonZero::new(range.end - range.start),
+                    }),
+                })
+                .collect::<Vec<_>>(),
+        });
+        *cache = Some(CachedBindGroup {
+            layout: layout.clone(),
+            entries: entries.to_vec(),
+            bind_group: bind_group.clone(),
+        });
+        bind_group
+    }
+}
+
+/// Pool of transient textures, e.g. intermediate render targets.
+///
+/// A texture returns to the pool when all its handles are dropped.
+/// Commands that use the texture are ordered by the GPU, so it can be reused before they are submitted.
+/// Textures that stay free for several submissions of frames or texture targets are dropped.
+#[derive(Clone)]
+pub struct TexturePool {
+    inner: Arc<TexturePoolInner>,
+}
+
+struct TexturePoolInner {
+    device: wgpu::Device,
+    slots: Mutex<Vec<Arc<TextureSlot>>>,
+    /// Number of submissions so far.
+    submission: AtomicU64,
+}
+
+struct TextureSlot {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    /// Submission during which the texture was used last time.
+    last_used: AtomicU64,
+}
+
+/// Texture borrowed from [`TexturePool`].
+#[derive(Clone, Debug)]
+pub struct PooledTexture {
+    slot: Arc<TextureSlot>,
+}
+
+/// Reference to a pooled texture that does not keep it borrowed.
+#[derive(Clone, Debug)]
+pub(crate) struct WeakTexture(Weak<TextureSlot>);
+
+impl TexturePool {
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        Self {
+            inner: Arc::new(TexturePoolInner {
+                device: device.clone(),
+                slots: Mutex::default(),
+                submission: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Takes a free 2D texture with the given parameters.
+    pub fn get(
+        &self,
+        size: (u32, u32),
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> PooledTexture {
+        let mut slots = self.inner.slots.lock().unwrap();
+        let slot = match slots.iter().find(|slot| {
+            let texture = &slot.texture;
+            Arc::strong_count(slot) == 1
+                && (texture.width(), texture.height()) == size
+                && texture.format() == format
+                && texture.usage() == usage
+        }) {
+            Some(slot) => slot.clone(),
+            None => {
+                let texture = self.inner.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("pooled"),
+                    size: wgpu::Extent3d {
+                        width: size.0,
+                        height: size.1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                });
+                let slot = Arc::new(TextureSlot {
+                    view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    texture,
+                    last_used: AtomicU64::new(0),
+                });
+                slots.push(slot.clone());
+                slot
+            }
+        };
+        (slot.last_used).store(
+            self.inner.submission.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+        PooledTexture { slot }
+    }
+
+    /// Drops all free textures, e.g. after targets are resized.
+    pub fn trim(&self) {
+        self.inner
+            .slots
+            .lock()
+            .unwrap()
+            .retain(|slot| Arc::strong_count(slot) > 1);
+    }
+
+    /// Drops textures that were not used during the last [`MAX_IDLE_SUBMISSIONS`], called after commands are submitted.
+    pub(crate) fn submitted(&self) {
+        let submission = self.inner.submission.fetch_add(1, Ordering::Relaxed) + 1;
+        self.inner.slots.lock().unwrap().retain(|slot| {
+            if Arc::strong_count(slot) > 1 {
+                slot.last_used.store(submission, Ordering::Relaxed);
+            }
+            submission - slot.last_used.load(Ordering::Relaxed) <= MAX_IDLE_SUBMISSIONS
+        });
+    }
+
+    /// Number of textures allocated by the pool.
+    pub fn len(&self) -> usize {
+        self.inner.slots.lock().unwrap().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PooledTexture {
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.slot.texture
+    }
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.slot.view
+    }
+    pub fn size(&self) -> (u32, u32) {
+        (self.slot.texture.width(), self.slot.texture.height())
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakTexture {
+        WeakTexture(Arc::downgrade(&self.slot))
+    }
+}
+
+impl WeakTexture {
+    /// Whether the texture has not been dropped by the pool yet.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.0.strong_count() > 0
+    }
+    pub(crate) fn refers_to(&self, texture: &PooledTexture) -> bool {
+        ptr::eq(self.0.as_ptr(), Arc::as_ptr(&texture.slot))
+    }
+}
+
+impl PartialEq for BufferPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+impl Eq for BufferPool {}
+impl Hash for BufferPool {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.inner).hash(state);
+    }
+}
+
+impl fmt::Debug for BufferPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+impl fmt::Debug for PooledBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledBuffer")
+            .field("buffer", &self.slot.buffer)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl PartialEq for TexturePool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+impl Eq for TexturePool {}
+impl Hash for TexturePool {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.inner).hash(state);
+    }
+}
+
+impl fmt::Debug for TexturePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TexturePool")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+impl fmt::Debug for TextureSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextureSlot")
+            .field("texture", &self.texture)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,539 @@
+use std::{borrow::Cow, cell::RefCell};
+
+use anyhow::Result;
+use glam::Vec4;
+use serde::Serialize;
+use wgame_shader::ShaderSource;
+
+use crate::{
+    BlendMode, ClipRect, Graphics, PipelineKey, PipelineState, PooledTexture, Profiler, Target,
+    pool::WeakTexture,
+};
+
+/// Source of full-screen post-processing pass.
+///
+/// Fragment source modifies `color: vec4<f32>` that initially contains the source pixel at `tex_coord`.
+/// Following items are available:
+/// + `sample_source(tex_coord)` - output of the previous pass, or the scene for the first one.
+/// + `sample_original(tex_coord)` - the scene before post-processing.
+/// + `sample_extra(tex_coord)` - additional texture of the effect, e.g. color grading LUT.
+/// + `linear_to_srgb(rgb)` - encodes linear color into sRGB.
+/// + `texel_size` - size of a single pixel in texture coordinates.
+/// + `params` - user parameters of the effect.
+#[derive(Clone, Default, Debug, Serialize)]
+pub struct EffectSource {
+    /// Additional WGSL items, e.g. helper functions.
+    pub source: String,
+    /// Body of the fragment shader.
+    pub fragment_source: String,
+}
+
+/// Single full-screen pass of [`PostProcess`] chain.
+#[derive(Clone, Debug)]
+pub struct PostEffect {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    params: Vec4,
+    texture: Option<wgpu::TextureView>,
+    scale: f32,
+    bind_groups: BindGroupCache,
+}
+
+/// Bind groups of an effect by the textures they bind, each of them has its own uniform buffer.
+///
+/// Clones of the effect may have different parameters, so they do not share the cache.
+#[derive(Default, Debug)]
+struct BindGroupCache(RefCell<Vec<CachedBindGroup>>);
+
+#[derive(Debug)]
+struct CachedBindGroup {
+    source: WeakTexture,
+    original: WeakTexture,
+    extra: Option<wgpu::TextureView>,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Clone for BindGroupCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PostEffect {
+    pub fn new(state: &Graphics, source: &EffectSource) -> Result<Self> {
+        Self::with_format(state, source, state.format())
+    }
+
+    /// Creates effect that writes into textures of the given `format` instead of the graphics' one.
+    pub(crate) fn with_format(
+        state: &Graphics,
+        source: &EffectSource,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self> {
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            },
+            count: None,
+        };
+        let bind_group_layout = state.bind_group_layout(&[
+            texture_entry(0),
+            texture_entry(1),
+            texture_entry(2),
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 4,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ]);
+
+        // Full-screen passes gain nothing from multisampling, so the last one writes into the resolved view.
+        let pipeline = state.render_pipeline(&PipelineKey {
+            source: Cow::Owned(
+                ShaderSource::new("shaders/post.wgsl", include_str!("../shaders/post.wgsl"))?
+                    .substitute(source)?,
+            ),
+            bind_group_layouts: vec![bind_group_layout.clone()],
+            vertex_buffers: Vec::new(),
+            state: PipelineState {
+                blend: BlendMode::Opaque,
+                ..Default::default()
+            },
+            target: Some(format),
+        });
+
+        Ok(Self {
+            bind_group_layout,
+            pipeline,
+            params: Vec4::ZERO,
+            texture: None,
+            scale: 1.0,
+            bind_groups: BindGroupCache::default(),
+        })
+    }
+
+    /// Effect that leaves the image unchanged.
+    pub fn passthrough(state: &Graphics) -> Result<Self> {
+        Self::new(state, &EffectSource::default())
+    }
+
+    pub fn params(&self) -> Vec4 {
+        self.params
+    }
+    /// Sets parameters that are passed to the shader as `params`.
+    pub fn set_params(&mut self, params: Vec4) {
+        self.params = params;
+    }
+    pub fn with_params(self, params: Vec4) -> Self {
+        Self { params, ..self }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+    /// Sets size of the effect output relative to the target, e.g. `0.5` to render at half resolution.
+    ///
+    /// The last effect of the chain always writes into the target, so its scale is ignored.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Sets texture that is available in the shader via `sample_extra`.
+    pub fn set_texture(&mut self, texture: Option<wgpu::TextureView>) {
+        self.texture = texture;
+    }
+    pub fn with_texture(self, texture: wgpu::TextureView) -> Self {
+        Self {
+            texture: Some(texture),
+            ..self
+        }
+    }
+}
+
+/// Chain of full-screen effects applied to the rendered scene.
+///
+/// The scene is rendered into an intermediate texture taken from [`TexturePool`](crate::TexturePool),
+/// then the effects are applied one by one, and the last of them writes into the target.
+/// Intermediate outputs are sized by [`PostEffect::scale`], built-in effects are in [`effects`](crate::effects) module.
+///
+/// With MSAA the last effect writes into the resolved view of the target, while its multisampled buffer keeps the scene.
+/// Anything rendered into the target after effects are applied is resolved over their output,
+/// so it should be rendered into the scene or into another post-processing target instead.
+pub struct PostProcess {
+    effects: Vec<PostEffect>,
+    passthrough: PostEffect,
+    passes: EffectPasses,
+}
+
+impl PostProcess {
+    pub fn new(state: &Graphics) -> Result<Self> {
+        Ok(Self {
+            effects: Vec::new(),
+            passthrough: PostEffect::passthrough(state)?,
+            passes: EffectPasses::new(state),
+        })
+    }
+
+    pub(crate) fn state(&self) -> &Graphics {
+        self.passes.state()
+    }
+
+    pub fn push(&mut self, effect: PostEffect) {
+        self.effects.push(effect);
+    }
+    pub fn effects(&self) -> &[PostEffect] {
+        &self.effects
+    }
+    /// Effects in the order of application, they can be modified between frames.
+    pub fn effects_mut(&mut self) -> &mut Vec<PostEffect> {
+        &mut self.effects
+    }
+
+    /// Returns target to render the scene into.
+    ///
+    /// Effects are applied to the `target` when the returned target is dropped.
+    /// Pending clear of the `target` is moved to the returned one.
+    /// If the `target` is a [`Viewport`](crate::Viewport), only its rectangle is overwritten,
+    /// and the pending clear is also applied to the rest of the underlying target.
+    pub fn begin<'a, T: Target + ?Sized>(&'a self, target: &'a mut T) -> PostTarget<'a, T> {
+        let scene = self.state().textures().get(
+            target.size(),
+            self.state().format(),
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let pending_clear = match target.viewport_rect() {
+            Some(_) => *target.pending_clear(),
+            None => target.pending_clear().take(),
+        };
+        let clear = Some(pending_clear.unwrap_or(wgpu::Color::TRANSPARENT));
+        // Attachments of a viewport have size of the whole underlying target, so they cannot be reused.
+        let attachments = target.viewport_rect().map(|rect| {
+            let size = (rect.width, rect.height);
+            (
+                self.state().create_multisample_view(size),
+                self.state().create_depth_view(size),
+            )
+        });
+        PostTarget {
+            post: self,
+            target,
+            scene,
+            clear,
+            depth_clear: true,
+            attachments,
+        }
+    }
+
+    fn apply<T: Target + ?Sized>(&self, target: &mut T, scene: &PooledTexture) {
+        let viewport = target.viewport_rect();
+        if let Some(rect) = viewport {
+            // Only the viewport is overwritten, so the rest of the target is cleared as usual.
+            target.flush();
+            if rect.is_empty() {
+                return;
+            }
+        } else {
+            // Whole target is overwritten, so there is no need to clear it.
+            *target.pending_clear() = None;
+        }
+        let size = target.size();
+        let output_view = target.view().clone();
+        let profiler = target.profiler().clone();
+
+        let effects: Vec<_> = if self.effects.is_empty() {
+            vec![&self.passthrough]
+        } else {
+            self.effects.iter().collect()
+        };
+        let mut source = scene.clone();
+        for (i, effect) in effects.iter().enumerate() {
+            let intermediate = (i + 1 < effects.len()).then(|| {
+                self.state().textures().get(
+                    scaled(size, effect.scale),
+                    self.state().format(),
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                )
+            });
+            let (view, rect) = match &intermediate {
+                Some(texture) => (texture.view(), None),
+                None => (&output_view, viewport),
+            };
+
+            self.passes.pass(
+                target.encoder(),
+                &profiler,
+                effect,
+                (&source, scene),
+                (view, rect),
+            );
+
+            if let Some(texture) = intermediate {
+                source = texture;
+            }
+        }
+    }
+}
+
+/// Resources shared by full-screen effect passes.
+pub(crate) struct EffectPasses {
+    state: Graphics,
+    sampler: wgpu::Sampler,
+    /// Placeholder for effects without extra texture.
+    blank: wgpu::TextureView,
+}
+
+impl EffectPasses {
+    pub(crate) fn new(state: &Graphics) -> Self {
+        let device = state.device();
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let blank = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("post_blank"),
+                size: wgpu::Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            state: state.clone(),
+            sampler,
+            blank,
+        }
+    }
+
+    pub(crate) fn state(&self) -> &Graphics {
+        &self.state
+    }
+
+    /// Records a single full-screen pass of the `effect` reading from `source` and `original` textures.
+    ///
+    /// If `rect` is set, the pass covers only this rectangle of the `view` and the rest of it is kept.
+    pub(crate) fn pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        profiler: &Profiler,
+        effect: &PostEffect,
+        (source, original): (&PooledTexture, &PooledTexture),
+        (view, rect): (&wgpu::TextureView, Option<ClipRect>),
+    ) {
+        let bind_group = self.bind_group(profiler, effect, source, original);
+        profiler.record_pass();
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("post"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: match rect {
+                        Some(_) => wgpu::LoadOp::Load,
+                        None => wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: profiler.pass_timestamps(),
+            occlusion_query_set: None,
+            multiview_mask: None,
+        });
+        if let Some(rect) = rect {
+            let (x, y, width, height) = (rect.x, rect.y, rect.width, rect.height);
+            pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+            pass.set_scissor_rect(x, y, width, height);
+        }
+        pass.set_pipeline(&effect.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+        profiler.record_draw(1);
+    }
+
+    /// Returns bind group of the `effect` for the given textures with its current parameters.
+    ///
+    /// Bind groups are created once per textures and are dropped along with the textures by the pool.
+    fn bind_group(
+        &self,
+        profiler: &Profiler,
+        effect: &PostEffect,
+        source: &PooledTexture,
+        original: &PooledTexture,
+    ) -> wgpu::BindGroup {
+        let (width, height) = source.size();
+        let params = effect.params;
+        let uniforms: [f32; 8] = [
+            1.0 / width as f32,
+            1.0 / height as f32,
+            0.0,
+            0.0,
+            params.x,
+            params.y,
+            params.z,
+            params.w,
+        ];
+        let mut cache = effect.bind_groups.0.borrow_mut();
+        cache.retain(|cached| {
+            cached.source.is_alive() && cached.original.is_alive() && cached.extra == effect.texture
+        });
+        let cached = match cache.iter().position(|cached| {
+            cached.source.refers_to(source) && cached.original.refers_to(original)
+        }) {
+            Some(index) => &cache[index],
+            None => {
+                cache.push(self.create_bind_group(effect, source, original));
+                cache.last().unwrap()
+            }
+        };
+        let uniforms: &[u8] = bytemuck::cast_slice(&uniforms);
+        (self.state.queue()).write_buffer(&cached.uniforms, 0, uniforms);
+        profiler.record_upload(uniforms.len() as u64);
+        cached.bind_group.clone()
+    }
+
+    fn create_bind_group(
+        &self,
+        effect: &PostEffect,
+        source: &PooledTexture,
+        original: &PooledTexture,
+    ) -> CachedBindGroup {
+        let device = self.state.device();
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("post"),
+            size: 8 * size_of::<f32>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post"),
+            layout: &effect.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(original.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(
+                        effect.texture.as_ref().unwrap_or(&self.blank),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: uniforms.as_entire_binding(),
+                },
+            ],
+        });
+        CachedBindGroup {
+            source: source.downgrade(),
+            original: original.downgrade(),
+            extra: effect.texture.clone(),
+            uniforms,
+            bind_group,
+        }
+    }
+}
+
+/// Size of the texture at `scale` of `size`, at least a single texel.
+fn scaled((width, height): (u32, u32), scale: f32) -> (u32, u32) {
+    let scale = |x: u32| ((x as f32 * scale).round() as u32).max(1);
+    (scale(width), scale(height))
+}
+
+/// Target that renders the scene before post-processing.
+///
+/// Effects are applied to the underlying target on drop.
+pub struct PostTarget<'a, T: Target + ?Sized> {
+    post: &'a PostProcess,
+    target: &'a mut T,
+    scene: PooledTexture,
+    clear: Option<wgpu::Color>,
+    depth_clear: bool,
+    /// Multisample and depth views of the scene, if attachments of the target cannot be reused.
+    attachments: Option<(Option<wgpu::TextureView>, Option<wgpu::TextureView>)>,
+}
+
+impl<T: Target + ?Sized> Target for PostTarget<'_, T> {
+    fn state(&self) -> &Graphics {
+        self.target.state()
+    }
+    fn profiler(&self) -> &Profiler {
+        self.target.profiler()
+    }
+    fn view(&self) -> &wgpu::TextureView {
+        self.scene.view()
+    }
+    // Attachments of the underlying target are reused if possible, because its contents are overwritten by effects anyway.
+    fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        match &self.attachments {
+            Some((multisample, _)) => multisample.as_ref(),
+            None => self.target.multisample_view(),
+        }
+    }
+    fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        match &self.attachments {
+            Some((_, depth)) => depth.as_ref(),
+            None => self.target.depth_view(),
+        }
+    }
+    fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        self.target.encoder()
+    }
+    fn pending_clear(&mut self) -> &mut Option<wgpu::Color> {
+        &mut self.clear
+    }
+    fn pending_depth_clear(&mut self) -> &mut bool {
+        match self.attachments {
+            Some(_) => &mut self.depth_clear,
+            None => self.target.pending_depth_clear(),
+        }
+    }
+}
+
+impl<T: Target + ?Sized> Drop for PostTarget<'_, T> {
+    fn drop(&mut self) {
+        self.flush();
+        self.post.apply(self.target, &self.scene);
+    }
+}
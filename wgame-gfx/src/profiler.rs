@@ -0,0 +1,306 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::channel::oneshot;
+use web_time::Instant;
+
+/// Maximum number of render passes per frame that are measured on GPU.
+const MAX_PASSES: u32 = 128;
+
+/// Counters and timings of a single frame.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct FrameStats {
+    pub render_passes: usize,
+    pub batches: usize,
+    pub draw_calls: usize,
+    pub instances: usize,
+    /// Bytes written to GPU buffers and textures.
+    pub uploaded_bytes: u64,
+    /// CPU time spent building scenes.
+    pub scene_time: Duration,
+    /// CPU time spent baking storages into renderers.
+    pub bake_time: Duration,
+    /// GPU time of all render passes.
+    ///
+    /// Available only if profiling is enabled and `TIMESTAMP_QUERY` feature is supported.
+    /// Timestamps are read back asynchronously, so this is the time of one of the previous frames.
+    pub gpu_time: Option<Duration>,
+}
+
+/// Collects frame statistics.
+///
+/// Each surface and texture target has its own profiler.
+/// Draws and uploads are recorded into the profiler of [`Graphics`](crate::Graphics),
+/// which passes them to the profiler of the target that is being rendered into,
+/// and keeps those that happen outside of rendering, e.g. texture loading.
+#[derive(Clone)]
+pub struct Profiler {
+    inner: Arc<ProfilerInner>,
+}
+
+struct ProfilerInner {
+    stats: Mutex<FrameStats>,
+    last_frame: Mutex<FrameStats>,
+    timer: Option<GpuTimer>,
+    /// Profiler of the target that is being rendered into.
+    active: Mutex<Option<Profiler>>,
+}
+
+/// Restores previously active profiler on drop.
+pub(crate) struct Activation {
+    profiler: Profiler,
+    previous: Option<Profiler>,
+}
+
+/// Measures duration of render passes using timestamp queries.
+struct GpuTimer {
+    device: wgpu::Device,
+    period: f32,
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Number of timestamps written in the current frame.
+    count: AtomicU32,
+    /// Number of timestamps that are being read back and the receiver of mapping result.
+    pending: Mutex<Option<(u32, PendingMap)>>,
+    /// Number of resolved timestamps that are about to be submitted.
+    resolved: Mutex<Option<u32>>,
+    last_time: Mutex<Option<Duration>>,
+}
+
+type PendingMap = oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+impl Profiler {
+    /// GPU timing is enabled only if `TIMESTAMP_QUERY` feature is enabled on the device.
+    pub(crate) fn new(device: &wgpu::Device, queue: &wgpu::Queue, gpu_timing: bool) -> Self {
+        let gpu_timing = gpu_timing && device.features().contains(wgpu::Features::TIMESTAMP_QUERY);
+        Self {
+            inner: Arc::new(ProfilerInner {
+                stats: Mutex::default(),
+                last_frame: Mutex::default(),
+                timer: gpu_timing.then(|| GpuTimer::new(device, queue)),
+                active: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Statistics collected since the last frame was presented.
+    pub fn stats(&self) -> FrameStats {
+        let mut stats = self.inner.stats.lock().unwrap().clone();
+        stats.gpu_time = self.gpu_time();
+        stats
+    }
+    /// Returns collected statistics and starts a new frame.
+    pub fn take(&self) -> FrameStats {
+        if let Some(timer) = &self.inner.timer {
+            timer.count.store(0, Ordering::Relaxed);
+        }
+        let mut stats = std::mem::take(&mut *self.inner.stats.lock().unwrap());
+        stats.gpu_time = self.gpu_time();
+        *self.inner.last_frame.lock().unwrap() = stats.clone();
+        stats
+    }
+    /// Statistics of the last presented frame.
+    pub fn last_frame(&self) -> FrameStats {
+        self.inner.last_frame.lock().unwrap().clone()
+    }
+
+    /// Redirects draws and uploads recorded into this profiler to the `target` one until the guard is dropped.
+    pub(crate) fn activate(&self, target: &Profiler) -> Activation {
+        Activation {
+            profiler: self.clone(),
+            previous: self.inner.active.lock().unwrap().replace(target.clone()),
+        }
+    }
+    fn with_active_stats(&self, f: impl FnOnce(&mut FrameStats)) {
+        match &*self.inner.active.lock().unwrap() {
+            Some(active) => f(&mut active.inner.stats.lock().unwrap()),
+            None => f(&mut self.inner.stats.lock().unwrap()),
+        }
+    }
+
+    pub fn record_draw(&self, instances: u32) {
+        self.with_active_stats(|stats| {
+            stats.draw_calls += 1;
+            stats.instances += instances as usize;
+        });
+    }
+    pub fn record_upload(&self, bytes: u64) {
+        self.with_active_stats(|stats| stats.uploaded_bytes += bytes);
+    }
+
+    pub(crate) fn record_pass(&self) {
+        self.inner.stats.lock().unwrap().render_passes += 1;
+    }
+    pub(crate) fn record_batch(&self) {
+        self.inner.stats.lock().unwrap().batches += 1;
+    }
+    pub(crate) fn record_scene_time(&self, start: Instant) {
+        self.inner.stats.lock().unwrap().scene_time += start.elapsed();
+    }
+    pub(crate) fn time_bake<R>(&self, bake: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = bake();
+        self.inner.stats.lock().unwrap().bake_time += start.elapsed();
+        result
+    }
+
+    /// Timestamp writes for the next render pass, if GPU timing is enabled and there are free queries.
+    pub(crate) fn pass_timestamps(&self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        let timer = self.inner.timer.as_ref()?;
+        let index = timer
+            .count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                (count + 2 <= 2 * MAX_PASSES).then_some(count + 2)
+            })
+            .ok()?;
+        Some(wgpu::RenderPassTimestampWrites {
+            query_set: &timer.query_set,
+            beginning_of_pass_write_index: Some(index),
+            end_of_pass_write_index: Some(index + 1),
+        })
+    }
+
+    /// Records copying of the frame timestamps for reading back.
+    pub(crate) fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(timer) = &self.inner.timer {
+            timer.resolve(encoder);
+        }
+    }
+    /// Starts reading back resolved timestamps, must be called after submission.
+    pub(crate) fn submitted(&self) {
+        if let Some(timer) = &self.inner.timer {
+            timer.map();
+        }
+    }
+
+    fn gpu_time(&self) -> Option<Duration> {
+        let timer = self.inner.timer.as_ref()?;
+        timer.poll();
+        *timer.last_time.lock().unwrap()
+    }
+}
+
+impl GpuTimer {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let size = 2 * MAX_PASSES as u64 * wgpu::QUERY_SIZE as u64;
+        Self {
+            device: device.clone(),
+            period: queue.get_timestamp_period(),
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("pass_timestamps"),
+                ty: wgpu::QueryType::Timestamp,
+                count: 2 * MAX_PASSES,
+            }),
+            resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("timestamp_resolve"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("timestamp_readback"),
+                size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            count: AtomicU32::new(0),
+            pending: Mutex::default(),
+            resolved: Mutex::default(),
+            last_time: Mutex::default(),
+        }
+    }
+
+    fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        let count = self.count.load(Ordering::Relaxed);
+        // Readback buffer is still in use by one of the previous frames, skip this one.
+        if count == 0 || self.pending.lock().unwrap().is_some() {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            count as u64 * wgpu::QUERY_SIZE as u64,
+        );
+        *self.resolved.lock().unwrap() = Some(count);
+    }
+
+    fn map(&self) {
+        let Some(count) = self.resolved.lock().unwrap().take() else {
+            return;
+        };
+        let (sender, receiver) = oneshot::channel();
+        self.readback_buffer
+            .map_async(wgpu::MapMode::Read, .., move |result| {
+                let _ = sender.send(result);
+            });
+        *self.pending.lock().unwrap() = Some((count, receiver));
+    }
+
+    /// Reads timestamps if they are mapped already.
+    fn poll(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        let Some((count, receiver)) = pending.as_mut() else {
+            return;
+        };
+        let _ = self.device.poll(wgpu::PollType::Poll);
+        match receiver.try_recv() {
+            Ok(None) => return,
+            Ok(Some(Ok(()))) => {
+                let ticks: u64 = {
+                    let data = self.readback_buffer.get_mapped_range(..);
+                    let timestamps: &[u64] =
+                        bytemuck::cast_slice(&data[..*count as usize * wgpu::QUERY_SIZE as usize]);
+                    timestamps
+                        .chunks_exact(2)
+                        .map(|pair| pair[1].saturating_sub(pair[0]))
+                        .sum()
+                };
+                self.readback_buffer.unmap();
+                *self.last_time.lock().unwrap() = Some(Duration::from_nanos(
+                    (ticks as f64 * self.period as f64) as u64,
+                ));
+            }
+            Ok(Some(Err(err))) => log::warn!("Failed to read timestamps: {err}"),
+            Err(_) => log::warn!("Timestamps mapping was cancelled"),
+        }
+        *pending = None;
+    }
+}
+
+impl Drop for Activation {
+    fn drop(&mut self) {
+        *self.profiler.inner.active.lock().unwrap() = self.previous.take();
+    }
+}
+
+impl PartialEq for Profiler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+impl Eq for Profiler {}
+impl Hash for Profiler {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.inner).hash(state);
+    }
+}
+
+impl fmt::Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profiler")
+            .field("stats", &self.inner.stats.lock().unwrap())
+            .field("gpu_timing", &self.inner.timer.is_some())
+            .finish()
+    }
+}
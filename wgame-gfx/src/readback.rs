@@ -0,0 +1,254 @@
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use anyhow::{Result, anyhow, bail};
+use futures::channel::oneshot;
+use half::f16;
+use rgb::{ComponentMap, Rgba};
+use wgame_image::{Image, Pixel};
+
+use crate::Graphics;
+
+/// Pixel type that texture contents can be read back into.
+pub trait ReadPixel: Pixel {
+    /// Converts tightly packed row of texels of `format` into pixels.
+    fn read_row(format: wgpu::TextureFormat, src: &[u8], dst: &mut Vec<Self>) -> Result<()>;
+}
+
+impl ReadPixel for Rgba<u8> {
+    fn read_row(format: wgpu::TextureFormat, src: &[u8], dst: &mut Vec<Self>) -> Result<()> {
+        use wgpu::TextureFormat::*;
+        let texels = bytemuck::cast_slice::<u8, Rgba<u8>>(src);
+        match format {
+            Rgba8Unorm | Rgba8UnormSrgb => dst.extend_from_slice(texels),
+            Bgra8Unorm | Bgra8UnormSrgb => {
+                dst.extend(texels.iter().map(|c| Rgba::new(c.b, c.g, c.r, c.a)))
+            }
+            other => bail!("Cannot read {other:?} texture as RGBA8 image"),
+        }
+        Ok(())
+    }
+}
+
+impl ReadPixel for Rgba<f16> {
+    fn read_row(format: wgpu::TextureFormat, src: &[u8], dst: &mut Vec<Self>) -> Result<()> {
+        use wgpu::TextureFormat::*;
+        match format {
+            Rgba16Float => dst.extend_from_slice(bytemuck::cast_slice::<u8, Rgba<f16>>(src)),
+            // 8-bit values are normalized, so that window surfaces can be read in any format.
+            Rgba8Unorm | Rgba8UnormSrgb | Bgra8Unorm | Bgra8UnormSrgb => {
+                let mut texels = Vec::with_capacity(src.len() / 4);
+                Rgba::<u8>::read_row(format, src, &mut texels)?;
+                dst.extend(
+                    texels
+                        .iter()
+                        .map(|c| c.map(|x| f16::from_f32(x as f32 / 255.0))),
+                )
+            }
+            Rgb10a2Unorm => dst.extend(bytemuck::cast_slice::<u8, u32>(src).iter().map(|&c| {
+                let unorm = |shift: u32, bits: u32| {
+                    let max = (1 << bits) - 1;
+                    f16::from_f32(((c >> shift) & max) as f32 / max as f32)
+                };
+                Rgba::new(unorm(0, 10), unorm(10, 10), unorm(20, 10), unorm(30, 2))
+            })),
+            other => bail!("Cannot read {other:?} texture as RGBA16F image"),
+        }
+        Ok(())
+    }
+}
+
+/// Texture contents copied into a staging buffer.
+pub(crate) struct Readback {
+    buffer: wgpu::Buffer,
+    format: wgpu::TextureFormat,
+    size: (u32, u32),
+    bytes_per_row: u32,
+}
+
+/// Readback which staging buffer is mapped after the frame is submitted.
+pub(crate) struct DeferredReadback {
+    buffer: wgpu::Buffer,
+    bytes_per_row: u32,
+    sender: oneshot::Sender<Result<(), wgpu::BufferAsyncError>>,
+}
+
+impl Readback {
+    /// Records copying of the whole `texture` into a staging buffer.
+    pub fn record(
+        state: &Graphics,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Result<Self> {
+        let this = Self::new(state, texture)?;
+        copy_texture(encoder, texture, &this.buffer, this.bytes_per_row);
+        Ok(this)
+    }
+
+    /// Allocates staging buffer for the whole `texture`.
+    ///
+    /// Fails if the texture format cannot be copied into a buffer, e.g. for depth-stencil formats.
+    pub fn new(state: &Graphics, texture: &wgpu::Texture) -> Result<Self> {
+        let format = texture.format();
+        let extent = texture.size();
+        let bytes_per_row = padded_bytes_per_row(format, extent.width)?;
+
+        let buffer = state.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: bytes_per_row as u64 * extent.height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self {
+            buffer,
+            format,
+            size: (extent.width, extent.height),
+            bytes_per_row,
+        })
+    }
+
+    /// Starts mapping of the staging buffer.
+    ///
+    /// Must be called after the commands recorded by [`Self::record`] are submitted.
+    pub fn map<P: ReadPixel>(self) -> PendingImage<P> {
+        let (image, deferred) = self.defer();
+        deferred.map();
+        image
+    }
+
+    /// Returns image that resolves after the copy is recorded and mapped using [`DeferredReadback`].
+    pub fn defer<P: ReadPixel>(self) -> (PendingImage<P>, DeferredReadback) {
+        let (sender, receiver) = oneshot::channel();
+        let deferred = DeferredReadback {
+            buffer: self.buffer.clone(),
+            bytes_per_row: self.bytes_per_row,
+            sender,
+        };
+        let image = PendingImage {
+            readback: Some(self),
+            receiver,
+            _ghost: PhantomData,
+        };
+        (image, deferred)
+    }
+
+    fn read<P: ReadPixel>(&self) -> Result<Image<P>> {
+        let data = self.buffer.get_mapped_range(..);
+        let pixels = read_rows(self.format, self.size, self.bytes_per_row, &data);
+        drop(data);
+        self.buffer.unmap();
+        Ok(Image::with_data(self.size, pixels?))
+    }
+}
+
+/// Size of a texture row in a buffer, rows must be aligned to [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] when copied.
+pub(crate) fn padded_bytes_per_row(format: wgpu::TextureFormat, width: u32) -> Result<u32> {
+    let Some(bytes_per_texel) = format.block_copy_size(None) else {
+        bail!("{format:?} texture cannot be copied into a buffer");
+    };
+    Ok((width * bytes_per_texel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT))
+}
+
+/// Converts texture `data` of `size` with rows padded to `bytes_per_row` into tightly packed pixels.
+pub(crate) fn read_rows<P: ReadPixel>(
+    format: wgpu::TextureFormat,
+    (width, height): (u32, u32),
+    bytes_per_row: u32,
+    data: &[u8],
+) -> Result<Vec<P>> {
+    let Some(bytes_per_texel) = format.block_copy_size(None) else {
+        bail!("{format:?} texture cannot be copied into a buffer");
+    };
+    let row_size = (width * bytes_per_texel) as usize;
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for row in data.chunks(bytes_per_row as usize).take(height as usize) {
+        P::read_row(format, &row[..row_size], &mut pixels)?;
+    }
+    Ok(pixels)
+}
+
+impl DeferredReadback {
+    /// Records copying of the `texture` into the staging buffer.
+    pub fn record(&self, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        copy_texture(encoder, texture, &self.buffer, self.bytes_per_row);
+    }
+
+    /// Starts mapping of the staging buffer, must be called after the copy is submitted.
+    pub fn map(self) {
+        let sender = self.sender;
+        self.buffer
+            .map_async(wgpu::MapMode::Read, .., move |result| {
+                let _ = sender.send(result);
+            });
+    }
+}
+
+fn copy_texture(
+    encoder: &mut wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+    buffer: &wgpu::Buffer,
+    bytes_per_row: u32,
+) {
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            depth_or_array_layers: 1,
+            ..texture.size()
+        },
+    );
+}
+
+/// Image that is being read back from GPU.
+///
+/// Resolves when the staging buffer is mapped, which requires the device to be polled.
+pub struct PendingImage<P: ReadPixel> {
+    readback: Option<Readback>,
+    receiver: oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>,
+    _ghost: PhantomData<fn() -> P>,
+}
+
+impl<P: ReadPixel> PendingImage<P> {
+    /// Returns the image if it is already mapped, without waiting.
+    pub fn try_take(&mut self) -> Option<Result<Image<P>>> {
+        match self.receiver.try_recv() {
+            Ok(None) => None,
+            Ok(Some(result)) => Some(self.finish(Ok(result))),
+            Err(canceled) => Some(self.finish(Err(canceled))),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        result: Result<Result<(), wgpu::BufferAsyncError>, oneshot::Canceled>,
+    ) -> Result<Image<P>> {
+        let readback = self
+            .readback
+            .take()
+            .ok_or_else(|| anyhow!("Image is already taken"))?;
+        result.map_err(|_| anyhow!("Buffer mapping was cancelled"))??;
+        readback.read()
+    }
+}
+
+impl<P: ReadPixel> Future for PendingImage<P> {
+    type Output = Result<Image<P>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => Poll::Ready(self.finish(result)),
+        }
+    }
+}
@@ -1,15 +1,59 @@
 use std::rc::Rc;
 
+use wgame_shader::BindingList;
+
+use crate::{Bounds, ClipRect};
+
 pub trait Context: 'static {
     fn bind_group(&self) -> wgpu::BindGroup;
+
+    /// Fields of global uniforms, known for the context type so that pipelines can be built for them in advance.
+    ///
+    /// Built-in renderers declare them in shaders as `globals` struct, see [`Camera::with_globals`](crate::Camera::with_globals).
+    fn globals() -> Option<BindingList>
+    where
+        Self: Sized,
+    {
+        None
+    }
+    /// Bind group with the global uniforms, set at index `1` right after the [`bind_group`](Self::bind_group).
+    ///
+    /// Its layout is [`Graphics::globals_bind_group_layout`](crate::Graphics::globals_bind_group_layout),
+    /// it must be provided if [`globals`](Self::globals) are.
+    fn globals_bind_group(&self) -> Option<wgpu::BindGroup> {
+        None
+    }
+
+    /// Checks whether anything inside of `bounds` can be visible, used to skip invisible instances.
+    fn is_visible(&self, bounds: &Bounds) -> bool {
+        let _ = bounds;
+        true
+    }
 }
 
 pub trait Renderer<C: Context> {
     fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>);
+
+    /// Rectangle outside of which nothing is drawn.
+    ///
+    /// The scissor rectangle is set by the target before rendering.
+    fn scissor(&self) -> Option<ClipRect> {
+        None
+    }
+    /// Stencil reference value that is set by the target before rendering.
+    fn stencil_reference(&self) -> u32 {
+        0
+    }
 }
 
 impl<C: Context, R: Renderer<C> + ?Sized> Renderer<C> for Rc<R> {
     fn render(&self, ctx: &C, pass: &mut wgpu::RenderPass<'_>) {
         (**self).render(ctx, pass);
     }
+    fn scissor(&self) -> Option<ClipRect> {
+        (**self).scissor()
+    }
+    fn stencil_reference(&self) -> u32 {
+        (**self).stencil_reference()
+    }
 }
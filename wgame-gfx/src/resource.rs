@@ -7,6 +7,8 @@ use std::{
 
 use smallvec::SmallVec;
 
+use crate::ClipRect;
+
 /// Shared resource required to draw an instance.
 ///
 /// Equality of the instances' resource means that they can be draw in single render pass.
@@ -14,6 +16,14 @@ pub trait Resource: Any + Eq + Hash + Clone + Sized {
     fn order(&self) -> impl Iterator<Item = i32> {
         iter::empty()
     }
+    /// Rectangle outside of which instances are not drawn.
+    fn scissor(&self) -> Option<ClipRect> {
+        None
+    }
+    /// Value that instances write into or test against the stencil buffer.
+    fn stencil_reference(&self) -> u32 {
+        0
+    }
 }
 
 pub trait AnyResource: Any + 'static {
@@ -21,6 +31,8 @@ pub trait AnyResource: Any + 'static {
     fn hash_dyn(&self, state: &mut dyn Hasher);
     fn eq_dyn(&self, other: &dyn AnyResource) -> bool;
     fn order_dyn(&self) -> SmallVec<[i32; 4]>;
+    fn scissor_dyn(&self) -> Option<ClipRect>;
+    fn stencil_reference_dyn(&self) -> u32;
 }
 
 impl<R: Resource> AnyResource for R {
@@ -43,6 +55,14 @@ impl<R: Resource> AnyResource for R {
     fn order_dyn(&self) -> SmallVec<[i32; 4]> {
         self.order().collect()
     }
+
+    fn scissor_dyn(&self) -> Option<ClipRect> {
+        self.scissor()
+    }
+
+    fn stencil_reference_dyn(&self) -> u32 {
+        self.stencil_reference()
+    }
 }
 
 impl PartialEq for dyn AnyResource {
@@ -61,6 +81,12 @@ impl Resource for Rc<dyn AnyResource> {
     fn order(&self) -> impl Iterator<Item = i32> {
         (**self).order_dyn().into_iter()
     }
+    fn scissor(&self) -> Option<ClipRect> {
+        (**self).scissor_dyn()
+    }
+    fn stencil_reference(&self) -> u32 {
+        (**self).stencil_reference_dyn()
+    }
 }
 
 impl From<&dyn AnyResource> for Rc<dyn AnyResource> {
@@ -0,0 +1,278 @@
+use std::{any::Any, cell::RefCell, collections::BTreeSet, rc::Rc};
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::{
+    AnyResource, AnyStorage, Camera, Context, Instance, InstanceVisitor, Object, Renderer, Target,
+    scene::cmp_order,
+};
+
+/// Handle of an object added to [`RetainedScene`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ObjectId(u64);
+
+/// Scene that keeps its objects between frames.
+///
+/// Objects are added once and then updated or removed by handle.
+/// Only batches whose contents changed are re-baked on render, others reuse their renderers.
+///
+/// Batches are drawn in the order of their resources, batches with equal order are drawn in the order of creation.
+pub struct RetainedScene<C: Context = Camera> {
+    next_id: u64,
+    next_sequence: u64,
+    objects: HashMap<ObjectId, Entry<C>>,
+    batches: HashMap<Rc<dyn AnyResource>, Batch<C>>,
+}
+
+struct Entry<C: Context> {
+    object: Box<dyn AnyObject<C>>,
+    resources: Vec<Rc<dyn AnyResource>>,
+}
+
+struct Batch<C: Context> {
+    /// Creation sequence number, used to order batches with equal resource order.
+    sequence: u64,
+    /// Objects that have instances in this batch, in the order of addition.
+    objects: BTreeSet<ObjectId>,
+    /// Baked renderer, `None` if the batch is dirty.
+    renderer: RefCell<Option<Rc<dyn Renderer<C>>>>,
+}
+
+/// Resource of a batch with its renderer.
+pub(crate) type BakedBatch<C> = (Rc<dyn AnyResource>, Rc<dyn Renderer<C>>);
+
+impl<C: Context> Default for RetainedScene<C> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            next_sequence: 0,
+            objects: HashMap::default(),
+            batches: HashMap::default(),
+        }
+    }
+}
+
+impl<C: Context> RetainedScene<C> {
+    pub fn add<T: Object<Context = C> + 'static>(&mut self, object: T) -> ObjectId {
+        let id = ObjectId(self.next_id);
+        self.next_id += 1;
+        self.insert(id, Box::new(object));
+        id
+    }
+
+    /// Replaces the object keeping its position in the scene.
+    ///
+    /// Only batches that contain instances of the old or the new object are re-baked,
+    /// batches that remain non-empty keep their position.
+    ///
+    /// Returns `false` if there is no object with such `id`.
+    pub fn update<T: Object<Context = C> + 'static>(&mut self, id: ObjectId, object: T) -> bool {
+        let Some(entry) = self.objects.get_mut(&id) else {
+            return false;
+        };
+        let object: Box<dyn AnyObject<C>> = Box::new(object);
+        let resources = collect_resources(object.as_ref());
+        let old = std::mem::replace(entry, Entry { object, resources });
+        for resource in old.resources {
+            if !self.objects[&id].resources.contains(&resource) {
+                self.remove_from_batch(id, &resource);
+            }
+        }
+        for resource in self.objects[&id].resources.clone() {
+            self.add_to_batch(id, resource);
+        }
+        true
+    }
+
+    /// Returns `false` if there is no object with such `id`.
+    pub fn remove(&mut self, id: ObjectId) -> bool {
+        let Some(entry) = self.objects.remove(&id) else {
+            return false;
+        };
+        for resource in entry.resources {
+            self.remove_from_batch(id, &resource);
+        }
+        true
+    }
+
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.objects.contains_key(&id)
+    }
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.batches.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+    /// Number of objects in the scene.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    fn insert(&mut self, id: ObjectId, object: Box<dyn AnyObject<C>>) {
+        let resources = collect_resources(object.as_ref());
+        for resource in &resources {
+            self.add_to_batch(id, resource.clone());
+        }
+        self.objects.insert(id, Entry { object, resources });
+    }
+
+    fn add_to_batch(&mut self, id: ObjectId, resource: Rc<dyn AnyResource>) {
+        let batch = self.batches.entry(resource).or_insert_with(|| {
+            let sequence = self.next_sequence;
+            self.next_sequence += 1;
+            Batch {
+                sequence,
+                objects: BTreeSet::new(),
+                renderer: RefCell::default(),
+            }
+        });
+        batch.objects.insert(id);
+        batch.invalidate();
+    }
+
+    fn remove_from_batch(&mut self, id: ObjectId, resource: &Rc<dyn AnyResource>) {
+        let batch = self.batches.get_mut(resource).unwrap();
+        batch.objects.remove(&id);
+        if batch.objects.is_empty() {
+            self.batches.remove(resource);
+        } else {
+            batch.invalidate();
+        }
+    }
+
+    /// Bakes changed batches and renders the scene into the `target`.
+    pub fn render<T: Target + ?Sized>(&self, target: &mut T, ctx: &C) {
+        let profiler = target.profiler().clone();
+        let _active = target.state().profiler().activate(&profiler);
+        let renderers = profiler.time_bake(|| self.bake_all());
+        target.render_iter(ctx, renderers.iter().map(|(_, renderer)| renderer));
+    }
+
+    /// Bakes changed batches and returns renderers of all batches in drawing order.
+    pub(crate) fn bake_all(&self) -> Vec<BakedBatch<C>> {
+        let mut batches: Vec<_> = self.batches.iter().collect();
+        batches.sort_by(|a, b| {
+            cmp_order(a.0.as_ref(), b.0.as_ref()).then(a.1.sequence.cmp(&b.1.sequence))
+        });
+        batches
+            .into_iter()
+            .map(|(resource, batch)| (resource.clone(), self.bake(resource.as_ref(), batch)))
+            .collect()
+    }
+
+    fn bake(&self, resource: &dyn AnyResource, batch: &Batch<C>) -> Rc<dyn Renderer<C>> {
+        batch
+            .renderer
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let mut storer = Storer {
+                    resource,
+                    storage: None,
+                };
+                for id in &batch.objects {
+                    self.objects[id].object.visit_dyn(&mut storer);
+                }
+                storer
+                    .storage
+                    .expect("Batch contains no instances")
+                    .bake_dyn()
+            })
+            .clone()
+    }
+}
+
+impl<C: Context> Batch<C> {
+    fn invalidate(&mut self) {
+        *self.renderer.get_mut() = None;
+    }
+}
+
+/// Object-safe version of [`Object`].
+trait AnyObject<C: Context> {
+    fn visit_dyn(&self, visitor: &mut dyn DynVisitor<C>);
+}
+
+impl<T: Object> AnyObject<T::Context> for T {
+    fn visit_dyn(&self, visitor: &mut dyn DynVisitor<T::Context>) {
+        self.for_each_instance(&mut DynVisitorRef(visitor));
+    }
+}
+
+/// Receives instances with their resources already type-erased.
+trait DynVisitor<C: Context> {
+    fn wants(&mut self, resource: &dyn AnyResource) -> bool;
+    fn store(&mut self, instance: &dyn AnyInstance<C>);
+}
+
+struct DynVisitorRef<'a, C: Context>(&'a mut dyn DynVisitor<C>);
+
+impl<C: Context> InstanceVisitor<C> for DynVisitorRef<'_, C> {
+    fn visit<T: Instance<Context = C>>(&mut self, instance: &T) {
+        if self.0.wants(&instance.resource()) {
+            self.0.store(instance);
+        }
+    }
+}
+
+trait AnyInstance<C: Context> {
+    fn new_storage_dyn(&self) -> Box<dyn AnyStorage<C>>;
+    fn store_dyn(&self, storage: &mut dyn AnyStorage<C>);
+}
+
+impl<T: Instance> AnyInstance<T::Context> for T {
+    fn new_storage_dyn(&self) -> Box<dyn AnyStorage<T::Context>> {
+        Box::new(self.new_storage())
+    }
+    fn store_dyn(&self, storage: &mut dyn AnyStorage<T::Context>) {
+        let storage = (storage as &mut dyn Any)
+            .downcast_mut::<T::Storage>()
+            .expect("Error downcasting storage during push");
+        self.store(storage);
+    }
+}
+
+fn collect_resources<C: Context>(object: &dyn AnyObject<C>) -> Vec<Rc<dyn AnyResource>> {
+    let mut collector = ResourceCollector::default();
+    object.visit_dyn(&mut collector);
+    collector.resources
+}
+
+/// Collects resources of all object instances.
+#[derive(Default)]
+struct ResourceCollector {
+    resources: Vec<Rc<dyn AnyResource>>,
+    unique: HashSet<Rc<dyn AnyResource>>,
+}
+
+impl<C: Context> DynVisitor<C> for ResourceCollector {
+    fn wants(&mut self, resource: &dyn AnyResource) -> bool {
+        if !self.unique.contains(resource) {
+            let resource = resource.clone_dyn();
+            self.unique.insert(resource.clone());
+            self.resources.push(resource);
+        }
+        false
+    }
+    fn store(&mut self, _: &dyn AnyInstance<C>) {}
+}
+
+/// Stores instances with the specific resource.
+struct Storer<'a, C: Context> {
+    resource: &'a dyn AnyResource,
+    storage: Option<Box<dyn AnyStorage<C>>>,
+}
+
+impl<C: Context> DynVisitor<C> for Storer<'_, C> {
+    fn wants(&mut self, resource: &dyn AnyResource) -> bool {
+        resource == self.resource
+    }
+    fn store(&mut self, instance: &dyn AnyInstance<C>) {
+        let storage = self
+            .storage
+            .get_or_insert_with(|| instance.new_storage_dyn());
+        instance.store_dyn(storage.as_mut());
+    }
+}
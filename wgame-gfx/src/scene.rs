@@ -1,31 +1,92 @@
 use std::{any::Any, cmp::Ordering, rc::Rc};
 
-use hashbrown::{HashMap, hash_map::EntryRef};
+use hashbrown::HashMap;
 
-use crate::{
-    AnyResource, AnyStorage, Camera, Context, Instance, InstanceVisitor, Object, Resource,
-};
+use crate::{AnyResource, AnyStorage, Bounds, Camera, Context, Instance, InstanceVisitor, Object};
+
+/// How instances are grouped into batches.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Batching {
+    /// All instances with the same resource are drawn in a single batch.
+    ///
+    /// Batches with equal order are drawn in order of their first instance.
+    #[default]
+    ByResource,
+    /// Instances are drawn in order they were added.
+    ///
+    /// Instance is merged into the latest batch with the same resource
+    /// if it does not overlap any instance added to later batches, otherwise a new batch is started.
+    /// Overlap is checked by [`bounds`](Instance::bounds) in XY plane, which is exact for 2D cameras,
+    /// and instances without bounds are considered to overlap everything.
+    Submission,
+}
+
+type Batch<C> = (Rc<dyn AnyResource>, Box<dyn AnyStorage<C>>);
 
 pub struct Scene<C: Context = Camera> {
-    items: HashMap<Rc<dyn AnyResource>, Box<dyn AnyStorage<C>>>,
+    batching: Batching,
+    items: Vec<Batch<C>>,
+    /// Bounds of instances in each item, `None` if some of them are unbounded.
+    bounds: Vec<Option<Bounds>>,
+    /// Indices of items by their resource, in [`Batching::Submission`] mode only the latest one is stored.
+    index: HashMap<Rc<dyn AnyResource>, usize>,
 }
 
 impl<C: Context> Default for Scene<C> {
     fn default() -> Self {
-        Self {
-            items: HashMap::default(),
-        }
+        Self::new(Batching::default())
     }
 }
 
 impl<C: Context> Scene<C> {
+    pub fn new(batching: Batching) -> Self {
+        Self {
+            batching,
+            items: Vec::new(),
+            bounds: Vec::new(),
+            index: HashMap::default(),
+        }
+    }
+
+    pub fn batching(&self) -> Batching {
+        self.batching
+    }
+    /// Changes batching mode, it applies only to instances added after the call.
+    pub fn set_batching(&mut self, batching: Batching) {
+        self.batching = batching;
+        self.index.clear();
+    }
+
     fn add_instance<T: Instance<Context = C>>(&mut self, instance: &T) {
         let resource = instance.resource();
-        let any_storage = match self.items.entry_ref(&resource as &dyn AnyResource) {
-            EntryRef::Vacant(entry) => entry.insert(Box::new(instance.new_storage())),
-            EntryRef::Occupied(entry) => entry.into_mut(),
+        let bounds = instance.bounds();
+        let index = self
+            .index
+            .get(&resource as &dyn AnyResource)
+            .copied()
+            .filter(|&i| match self.batching {
+                Batching::ByResource => true,
+                // Merging into earlier batch reorders the instance with all batches after it.
+                Batching::Submission => !self.bounds[(i + 1)..]
+                    .iter()
+                    .any(|other| overlaps(bounds.as_ref(), other.as_ref())),
+            });
+        let index = match index {
+            Some(index) => {
+                let merged = &mut self.bounds[index];
+                *merged = merged.zip(bounds).map(|(a, b)| a.union(b));
+                index
+            }
+            None => {
+                let resource = resource.clone_dyn();
+                self.index.insert(resource.clone(), self.items.len());
+                self.items
+                    .push((resource, Box::new(instance.new_storage())));
+                self.bounds.push(bounds);
+                self.items.len() - 1
+            }
         };
-        let storage = (any_storage.as_mut() as &mut dyn Any)
+        let storage = (self.items[index].1.as_mut() as &mut dyn Any)
             .downcast_mut::<T::Storage>()
             .expect("Error downcasting storage during push");
         instance.store(storage);
@@ -34,29 +95,27 @@ impl<C: Context> Scene<C> {
     pub fn add<T: Object<Context = C>>(&mut self, object: &T) {
         object.for_each_instance(self);
     }
+    /// Adds only those instances of `object` that are visible in `context`.
+    ///
+    /// Instances without [`bounds`](Instance::bounds) are always added.
+    pub fn add_visible<T: Object<Context = C>>(&mut self, object: &T, context: &C) {
+        object.for_each_instance(&mut Culled {
+            scene: self,
+            context,
+        });
+    }
 
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+    /// Number of batches.
     pub fn len(&self) -> usize {
         self.items.len()
     }
     pub fn iter(&self) -> impl Iterator<Item = &dyn AnyStorage<C>> {
         let mut items: Vec<_> = self.items.iter().collect();
-        items.sort_by(|a, b| {
-            let (mut a, mut b) = (a.0.order(), b.0.order());
-            loop {
-                let (a, b) = (a.next(), b.next());
-                if a.is_none() && b.is_none() {
-                    break Ordering::Equal;
-                }
-                let (a, b) = (a.unwrap_or(0), b.unwrap_or(0));
-                match a.cmp(&b) {
-                    Ordering::Equal => (),
-                    ord => break ord,
-                }
-            }
-        });
+        // Stable sort preserves order of addition for batches with equal order.
+        items.sort_by(|a, b| cmp_order(a.0.as_ref(), b.0.as_ref()));
         items.into_iter().map(|(_, s)| s.as_ref())
     }
 }
@@ -66,3 +125,46 @@ impl<C: Context> InstanceVisitor<C> for Scene<C> {
         self.add_instance(instance);
     }
 }
+
+/// Visitor that skips instances which are not visible in the context.
+struct Culled<'a, C: Context> {
+    scene: &'a mut Scene<C>,
+    context: &'a C,
+}
+
+impl<C: Context> InstanceVisitor<C> for Culled<'_, C> {
+    fn visit<T: Instance<Context = C>>(&mut self, instance: &T) {
+        if instance
+            .bounds()
+            .is_none_or(|bounds| self.context.is_visible(&bounds))
+        {
+            self.scene.add_instance(instance);
+        }
+    }
+}
+
+/// Checks whether boxes overlap in XY plane, unknown bounds overlap everything.
+fn overlaps(a: Option<&Bounds>, b: Option<&Bounds>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y
+        }
+        _ => true,
+    }
+}
+
+/// Compares resources by their rendering order.
+pub(crate) fn cmp_order(a: &dyn AnyResource, b: &dyn AnyResource) -> Ordering {
+    let (mut a, mut b) = (a.order_dyn().into_iter(), b.order_dyn().into_iter());
+    loop {
+        let (a, b) = (a.next(), b.next());
+        if a.is_none() && b.is_none() {
+            break Ordering::Equal;
+        }
+        let (a, b) = (a.unwrap_or(0), b.unwrap_or(0));
+        match a.cmp(&b) {
+            Ordering::Equal => (),
+            ord => break ord,
+        }
+    }
+}
@@ -1,32 +1,219 @@
-use crate::Camera;
+use std::{
+    hash::{Hash, Hasher},
+    num::NonZero,
+    sync::{Arc, Mutex},
+};
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+use anyhow::{Context as _, Result, bail};
+use derivative::Derivative;
+use wgame_shader::BindingList;
+
+use crate::{
+    BufferPool, Camera, ColorSpace, Config, PipelineKey, Profiler, TexturePool,
+    cache::PipelineCache,
+};
+
+#[derive(Clone, Derivative, Debug)]
+#[derivative(PartialEq, Eq, Hash)]
 pub struct Graphics {
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    instance: wgpu::Instance,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    config: Config,
+    /// Adapter belongs to the instance, so the instance is not compared.
     adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
     format: wgpu::TextureFormat,
+    sample_count: u32,
+    depth_format: Option<wgpu::TextureFormat>,
+    color_space: ColorSpace,
 
     camera_bind_group_layout: wgpu::BindGroupLayout,
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pipelines: PipelineCache,
+    buffers: BufferPool,
+    textures: TexturePool,
+    profiler: Profiler,
+    device_lost: DeviceLost,
+    /// State that replaced this one after the device was lost, shared by all surfaces of this state.
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    replacement: Arc<Mutex<Option<Graphics>>>,
+}
+
+/// Reason of the device loss, set by the device callback.
+#[derive(Clone, Default, Debug)]
+struct DeviceLost(Arc<Mutex<Option<String>>>);
+
+impl DeviceLost {
+    fn new(device: &wgpu::Device) -> Self {
+        let this = Self::default();
+        let reason = this.0.clone();
+        device.set_device_lost_callback(move |kind, message| {
+            match kind {
+                wgpu::DeviceLostReason::Destroyed => log::debug!("Device destroyed: {message}"),
+                _ => log::error!("Device lost: {message}"),
+            }
+            *reason.lock().unwrap() = Some(message);
+        });
+        this
+    }
+}
+
+impl PartialEq for DeviceLost {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for DeviceLost {}
+impl Hash for DeviceLost {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
 }
 
 impl Graphics {
     pub(crate) fn new(
+        instance: wgpu::Instance,
         adapter: wgpu::Adapter,
         device: wgpu::Device,
         queue: wgpu::Queue,
         format: wgpu::TextureFormat,
+        config: &Config,
     ) -> Self {
+        let sample_count = [Some(format), config.depth_format]
+            .into_iter()
+            .flatten()
+            .fold(config.msaa_samples, |count, format| {
+                Self::supported_sample_count(&adapter, &device, format, count)
+            });
+        // Render passes are timed by profilers of targets.
+        let profiler = Profiler::new(&device, &queue, false);
         Self {
             camera_bind_group_layout: Camera::create_bind_group_layout(&device),
+            pipelines: PipelineCache::new(),
+            buffers: BufferPool::new(&device, &queue, &profiler),
+            textures: TexturePool::new(&device),
+            profiler,
+            device_lost: DeviceLost::new(&device),
+            replacement: Arc::default(),
+            config: config.clone(),
+
+            instance,
 
             adapter,
             device,
             queue,
             format,
+            sample_count,
+            depth_format: config.depth_format,
+            color_space: config.color_space,
         }
     }
 
+    /// Sample counts other than guaranteed ones (`1` and `4`) can be used only
+    /// if `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature is enabled.
+    fn supported_sample_count(
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        count: u32,
+    ) -> u32 {
+        if count <= 1 {
+            return 1;
+        }
+        let features = if device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            adapter.get_texture_format_features(format)
+        } else {
+            format.guaranteed_format_features(device.features())
+        };
+        if features.flags.sample_count_supported(count) {
+            count
+        } else {
+            log::warn!("MSAA with {count} samples is not supported for {format:?}, disabling it");
+            1
+        }
+    }
+
+    /// Creates graphics state that is not bound to any window surface.
+    ///
+    /// Rendering is performed into targets of the given `format`, e.g. [`TextureTarget`](crate::TextureTarget).
+    /// With [`ColorSpace::Linear`] its sRGB variant is used instead.
+    pub async fn headless(config: &Config, format: wgpu::TextureFormat) -> Result<Self> {
+        let format = match config.color_space {
+            ColorSpace::Srgb => format,
+            ColorSpace::Linear => format.add_srgb_suffix(),
+        };
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
+        let (adapter, device, queue) = Self::request_device(&instance, config, None).await?;
+
+        let features = adapter.get_texture_format_features(format);
+        if !features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        {
+            bail!("Format {format:?} cannot be used as render target");
+        }
+
+        Ok(Self::new(instance, adapter, device, queue, format, config))
+    }
+
+    pub(crate) async fn request_device(
+        instance: &wgpu::Instance,
+        config: &Config,
+        compatible_surface: Option<&wgpu::Surface<'_>>,
+    ) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter: config.force_fallback_adapter,
+                compatible_surface,
+            })
+            .await
+            .context("Failed to find an appropriate adapter")?;
+
+        let mut features = wgpu::Features::empty();
+        if config.profiling {
+            features |= wgpu::Features::TIMESTAMP_QUERY;
+        }
+        if config.msaa_samples > 1 {
+            features |= wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        }
+        // Compute shaders are requested only if supported, e.g. they are not available in WebGL2.
+        let limits = if adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        {
+            wgpu::Limits::downlevel_defaults()
+        } else {
+            wgpu::Limits::downlevel_webgl2_defaults()
+        };
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: None,
+                required_features: adapter.features() & features,
+                required_limits: limits.using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::Performance,
+                trace: wgpu::Trace::Off,
+                experimental_features: Default::default(),
+            })
+            .await
+            .context("Failed to create device")?;
+
+        Ok((adapter, device, queue))
+    }
+
+    pub fn instance(&self) -> &wgpu::Instance {
+        &self.instance
+    }
+    /// Configuration the state was created with.
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
     pub fn adapter(&self) -> &wgpu::Adapter {
         &self.adapter
     }
@@ -39,8 +226,127 @@ impl Graphics {
     pub fn format(&self) -> wgpu::TextureFormat {
         self.format
     }
+    /// Number of samples per pixel of render targets.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Color space in which rendering is performed.
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    /// Format of depth buffer of render targets, if any.
+    pub fn depth_format(&self) -> Option<wgpu::TextureFormat> {
+        self.depth_format
+    }
+
+    /// Creates multisampled color buffer to render into before resolving to the target of the given size.
+    ///
+    /// Returns `None` if MSAA is disabled.
+    pub(crate) fn create_multisample_view(&self, size: (u32, u32)) -> Option<wgpu::TextureView> {
+        if self.sample_count <= 1 {
+            return None;
+        }
+        Some(self.create_attachment("multisample", self.format, size))
+    }
+
+    /// Creates depth buffer for the target of the given size, if depth buffer is enabled.
+    pub(crate) fn create_depth_view(&self, size: (u32, u32)) -> Option<wgpu::TextureView> {
+        let format = self.depth_format?;
+        Some(self.create_attachment("depth", format, size))
+    }
+
+    fn create_attachment(
+        &self,
+        label: &str,
+        format: wgpu::TextureFormat,
+        size: (u32, u32),
+    ) -> wgpu::TextureView {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
 
     pub fn camera_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
         &self.camera_bind_group_layout
     }
+    /// Layout of the bind group with global uniforms, see [`Context::globals`](crate::Context::globals).
+    pub fn globals_bind_group_layout(&self, globals: &BindingList) -> wgpu::BindGroupLayout {
+        let (_, size) = globals.uniform_layout();
+        self.bind_group_layout(&[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: NonZero::new(size),
+            },
+            count: None,
+        }])
+    }
+    /// Layout with the given `entries`, layouts are shared so that pipelines using them can be shared too.
+    pub fn bind_group_layout(
+        &self,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> wgpu::BindGroupLayout {
+        self.pipelines.bind_group_layout(&self.device, entries)
+    }
+    /// Render pipeline described by `key`, it is created once and then reused.
+    pub fn render_pipeline(&self, key: &PipelineKey) -> wgpu::RenderPipeline {
+        self.pipelines.render_pipeline(self, key)
+    }
+    /// Pool of reusable buffers for per-frame data.
+    pub fn buffers(&self) -> &BufferPool {
+        &self.buffers
+    }
+    /// Pool of transient textures.
+    pub fn textures(&self) -> &TexturePool {
+        &self.textures
+    }
+    /// Draws and uploads that are not attributed to any target, see [`Profiler`].
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+    /// Creates profiler for a new target, render passes are timed if profiling is enabled.
+    pub(crate) fn create_profiler(&self) -> Profiler {
+        Profiler::new(&self.device, &self.queue, self.config.profiling)
+    }
+
+    /// Whether the device has been lost.
+    ///
+    /// All resources of the lost device are invalid and must be created again on a new one,
+    /// [`Surface`](crate::Surface) does this automatically when acquiring the next frame.
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.0.lock().unwrap().is_some()
+    }
+
+    /// The latest state that was created instead of this one after the device loss.
+    ///
+    /// It is created by the first [`Surface`](crate::Surface) of this state that acquires a frame after the loss.
+    pub fn replacement(&self) -> Option<Graphics> {
+        let mut latest = self.replacement.lock().unwrap().clone()?;
+        loop {
+            let next = latest.replacement.lock().unwrap().clone();
+            match next {
+                Some(next) => latest = next,
+                None => break Some(latest),
+            }
+        }
+    }
+    pub(crate) fn set_replacement(&self, state: &Graphics) {
+        *self.replacement.lock().unwrap() = Some(state.clone());
+    }
 }
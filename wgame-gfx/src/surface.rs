@@ -1,12 +1,25 @@
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, bail};
 
-use crate::{Config, Frame, Graphics};
+use crate::{ColorSpace, Config, Frame, Graphics, Profiler, Tonemapper, hdr::HDR_FORMAT};
 
 pub struct Surface<'a> {
-    config: Config,
     surface: wgpu::Surface<'a>,
     state: Graphics,
+    profiler: Profiler,
+    /// Format of surface textures, it may differ from the format of their views.
+    format: wgpu::TextureFormat,
+    view_format: wgpu::TextureFormat,
+    /// Present only in HDR mode.
+    tonemapper: Option<Tonemapper>,
     size: (u32, u32),
+    multisample: Option<wgpu::TextureView>,
+    depth: Option<wgpu::TextureView>,
+    /// Surface must be reconfigured before acquiring the next texture.
+    outdated: bool,
+    /// Texture acquired for the next frame.
+    acquired: Option<wgpu::SurfaceTexture>,
+    /// Device was recreated and the next frame has not reported it yet.
+    device_recreated: bool,
 }
 
 impl<'a> Surface<'a> {
@@ -20,80 +33,284 @@ impl<'a> Surface<'a> {
             .create_surface(window_handle)
             .context("Failed to create surface")?;
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .context("Failed to find an appropriate adapter")?;
-
-        let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor {
-                label: None,
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::downlevel_webgl2_defaults()
-                    .using_resolution(adapter.limits()),
-                memory_hints: wgpu::MemoryHints::Performance,
-                trace: wgpu::Trace::Off,
-                experimental_features: Default::default(),
-            })
-            .await
-            .context("Failed to create device")?;
+        let (state, format) = Self::create_state(&instance, &surface, &config).await?;
+        Self::from_state(state, surface, format)
+    }
 
-        let caps = surface.get_capabilities(&adapter);
-        let format = (caps.formats.iter().copied())
-            .find(|format| !format.is_srgb())
-            .unwrap_or_else(|| caps.formats[0]);
+    /// Creates surface that renders using existing graphics state.
+    ///
+    /// Resources, e.g. textures and fonts, created for the `state` can be rendered into any of its surfaces.
+    /// The `state` must be created with a window surface or with compatible format,
+    /// configuration of the state (MSAA, color space, HDR) applies to the surface as well.
+    ///
+    /// When the device is lost, the first surface that acquires a frame recreates the state,
+    /// and the other surfaces sharing it switch to the new state.
+    /// Next frame of each surface reports it by [`Frame::device_recreated`].
+    pub fn with_graphics(
+        state: &Graphics,
+        window_handle: impl Into<wgpu::SurfaceTarget<'a>>,
+    ) -> Result<Self> {
+        let surface = state
+            .instance()
+            .create_surface(window_handle)
+            .context("Failed to create surface")?;
+        let format = Self::shared_format(state, &surface)?;
+        Self::from_state(state.clone(), surface, format)
+    }
 
-        let this = Self {
+    /// Finds surface format compatible with existing graphics state.
+    fn shared_format(state: &Graphics, surface: &wgpu::Surface<'a>) -> Result<wgpu::TextureFormat> {
+        let caps = surface.get_capabilities(state.adapter());
+        if caps.formats.is_empty() {
+            bail!("Surface is not supported by the adapter");
+        }
+        let config = state.config();
+        if config.hdr.is_some() {
+            Ok(Self::preferred_format(&caps, config))
+        } else {
+            (caps.formats.iter().copied())
+                .find(|format| Self::view_format_for(*format, config) == state.format())
+                .with_context(|| format!("Surface does not support {:?} format", state.format()))
+        }
+    }
+
+    /// Creates graphics state compatible with the surface, returns it with the surface format.
+    async fn create_state(
+        instance: &wgpu::Instance,
+        surface: &wgpu::Surface<'a>,
+        config: &Config,
+    ) -> Result<(Graphics, wgpu::TextureFormat)> {
+        let (adapter, device, queue) =
+            Graphics::request_device(instance, config, Some(surface)).await?;
+
+        let caps = surface.get_capabilities(&adapter);
+        let format = Self::preferred_format(&caps, config);
+        let state = Graphics::new(
+            instance.clone(),
+            adapter,
+            device,
+            queue,
+            if config.hdr.is_some() {
+                HDR_FORMAT
+            } else {
+                Self::view_format_for(format, config)
+            },
             config,
+        );
+        Ok((state, format))
+    }
+
+    fn from_state(
+        state: Graphics,
+        surface: wgpu::Surface<'a>,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self> {
+        let view_format = Self::view_format_for(format, state.config());
+        let tonemapper = (state.config().hdr)
+            .map(|hdr| Tonemapper::new(&state, view_format, hdr))
+            .transpose()?;
+        Ok(Self {
             surface,
-            state: Graphics::new(adapter, device, queue, format),
+            profiler: state.create_profiler(),
+            state,
+            format,
+            view_format,
+            tonemapper,
             size: Default::default(),
-        };
+            multisample: None,
+            depth: None,
+            outdated: false,
+            acquired: None,
+            device_recreated: false,
+        })
+    }
 
-        Ok(this)
+    fn preferred_format(caps: &wgpu::SurfaceCapabilities, config: &Config) -> wgpu::TextureFormat {
+        let hdr_format =
+            (config.hdr.is_some() && caps.formats.contains(&HDR_FORMAT)).then_some(HDR_FORMAT);
+        hdr_format.unwrap_or_else(|| match config.color_space {
+            ColorSpace::Srgb => (caps.formats.iter().copied())
+                .find(|format| !format.is_srgb())
+                .unwrap_or_else(|| caps.formats[0]),
+            ColorSpace::Linear => (caps.formats.iter().copied())
+                .find(|format| format.is_srgb())
+                .unwrap_or_else(|| caps.formats[0]),
+        })
     }
 
-    fn configure(&self) {
+    /// If there is no sRGB surface format, textures are viewed as sRGB to encode colors on write.
+    fn view_format_for(format: wgpu::TextureFormat, config: &Config) -> wgpu::TextureFormat {
+        match config.color_space {
+            ColorSpace::Srgb => format,
+            ColorSpace::Linear => format.add_srgb_suffix(),
+        }
+    }
+
+    /// Creates new device after the previous one was lost.
+    ///
+    /// The device is recreated only once for all surfaces sharing the state, the others switch to the new state.
+    async fn recreate_device(&mut self) -> Result<()> {
+        let config = self.state.config().clone();
+        let (state, format) = match self.state.replacement() {
+            Some(state) => {
+                let format = Self::shared_format(&state, &self.surface)?;
+                (state, format)
+            }
+            None => {
+                log::info!("Recreating lost device");
+                let (state, format) =
+                    Self::create_state(self.state.instance(), &self.surface, &config).await?;
+                self.state.set_replacement(&state);
+                (state, format)
+            }
+        };
+        let view_format = Self::view_format_for(format, &config);
+        let tonemapper = config
+            .hdr
+            .map(|hdr| {
+                // Tonemapping settings could be changed at runtime.
+                let hdr = self.tonemapper.as_ref().map_or(hdr, |old| old.config());
+                Tonemapper::new(&state, view_format, hdr)
+            })
+            .transpose()?;
+        self.profiler = state.create_profiler();
+        self.state = state;
+        self.format = format;
+        self.view_format = view_format;
+        self.tonemapper = tonemapper;
+        self.configure()?;
+        self.device_recreated = true;
+        Ok(())
+    }
+
+    fn configure(&mut self) -> Result<()> {
         let size = self.size;
         if let (0, _) | (_, 0) = size {
             log::debug!("Invalid surface size: {size:?}, skipping configuration");
-            return;
+            return Ok(());
         }
+        // Surface cannot be configured while its texture is alive.
+        self.acquired = None;
         let surface_config = self
             .surface
             .get_default_config(self.state.adapter(), size.0, size.1)
-            .unwrap();
+            .context("Surface is not supported by the adapter")?;
+        // Allows capturing frames if supported.
+        let copy_usage = self.surface.get_capabilities(self.state.adapter()).usages
+            & wgpu::TextureUsages::COPY_SRC;
         self.surface.configure(
             self.state.device(),
             &wgpu::SurfaceConfiguration {
-                format: self.state.format(),
-                present_mode: self.config.present_mode,
+                format: self.format,
+                view_formats: if self.format != self.view_format {
+                    vec![self.view_format]
+                } else {
+                    Vec::new()
+                },
+                present_mode: self.state.config().present_mode,
+                usage: surface_config.usage | copy_usage,
                 ..surface_config
             },
         );
+        self.multisample = self.state.create_multisample_view(size);
+        self.depth = self.state.create_depth_view(size);
+        self.state.textures().trim();
+        self.outdated = false;
+        Ok(())
     }
 
-    pub(crate) fn inner(&self) -> &wgpu::Surface<'a> {
-        &self.surface
+    pub(crate) fn view_format(&self) -> wgpu::TextureFormat {
+        self.view_format
+    }
+    pub(crate) fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        self.multisample.as_ref()
+    }
+    pub(crate) fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth.as_ref()
+    }
+    /// Returns whether the device was recreated since the previous call.
+    pub(crate) fn take_device_recreated(&mut self) -> bool {
+        std::mem::take(&mut self.device_recreated)
     }
 
     pub fn size(&self) -> (u32, u32) {
         self.size
     }
-    pub fn resize(&mut self, new_size: (u32, u32)) {
+    pub fn resize(&mut self, new_size: (u32, u32)) -> Result<()> {
         self.size = new_size;
-        self.configure();
+        self.configure()
+    }
+
+    /// Acquires the next frame.
+    ///
+    /// Outdated or lost surface is reconfigured, and lost device is recreated, see [`Frame::device_recreated`].
+    /// Returns `None` if the frame should be skipped, e.g. on timeout or if the surface has zero size.
+    pub async fn frame(&mut self) -> Result<Option<Frame<'a, '_>>> {
+        if !self.acquire().await? {
+            return Ok(None);
+        }
+        let texture = self.acquired.take().unwrap();
+        Ok(Some(Frame::new(self, texture)))
     }
 
-    pub fn frame(&mut self) -> Result<Frame<'a, '_>> {
-        Frame::new(self)
+    /// Acquires texture for the next frame in advance.
+    ///
+    /// Returns `false` if the frame should be skipped, otherwise the next call to [`Self::frame`] returns it.
+    pub async fn acquire(&mut self) -> Result<bool> {
+        if self.acquired.is_some() {
+            return Ok(true);
+        }
+        if self.state.is_device_lost() {
+            self.recreate_device().await?;
+        }
+        if let (0, _) | (_, 0) = self.size {
+            return Ok(false);
+        }
+        if self.outdated {
+            self.configure()?;
+        }
+        let texture = match self.surface.get_current_texture() {
+            Ok(texture) => texture,
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                log::debug!("Surface is outdated, reconfiguring");
+                self.configure()?;
+                match self.surface.get_current_texture() {
+                    Ok(texture) => texture,
+                    Err(err @ (wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Timeout)) => {
+                        log::warn!("Skipping frame: {err}");
+                        return Ok(false);
+                    }
+                    Err(err) => {
+                        return Err(err).context("Failed to acquire next swap chain texture");
+                    }
+                }
+            }
+            Err(wgpu::SurfaceError::Timeout) => {
+                log::warn!("Timeout acquiring swap chain texture, skipping frame");
+                return Ok(false);
+            }
+            Err(err) => return Err(err).context("Failed to acquire next swap chain texture"),
+        };
+        if texture.suboptimal {
+            self.outdated = true;
+        }
+        self.acquired = Some(texture);
+        Ok(true)
     }
 
     pub fn state(&self) -> &Graphics {
         &self.state
     }
+    /// Profiler of frames presented to the surface.
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+
+    /// Tonemapper that converts rendered HDR frames for presentation, if HDR is enabled.
+    pub fn tonemapper(&self) -> Option<&Tonemapper> {
+        self.tonemapper.as_ref()
+    }
+    pub fn tonemapper_mut(&mut self) -> Option<&mut Tonemapper> {
+        self.tonemapper.as_mut()
+    }
 }
@@ -1,66 +1,167 @@
-use crate::{AutoScene, Camera, Context, Graphics, Renderer, types::Color};
+use std::iter;
+
 use glam::Mat4;
 use rgb::{ComponentMap, Rgba};
 
+use crate::{
+    AutoScene, Camera, ClipRect, ComputePipeline, Context, Graphics, Profiler, Renderer, Viewport,
+    types::Color,
+};
+
 /// Render target
 pub trait Target {
     fn state(&self) -> &Graphics;
+    /// Profiler that collects statistics of rendering into the target.
+    fn profiler(&self) -> &Profiler;
     fn view(&self) -> &wgpu::TextureView;
     fn encoder(&mut self) -> &mut wgpu::CommandEncoder;
+    /// Multisampled color buffer that is resolved into [`Self::view`], if MSAA is enabled.
+    fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        None
+    }
+    /// Depth (and stencil) buffer of the target, if enabled.
+    fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        None
+    }
+    /// Rectangle of the view that is rendered into, `None` means the whole view.
+    fn viewport_rect(&self) -> Option<ClipRect> {
+        None
+    }
 
     fn size(&self) -> (u32, u32) {
         let extent = self.view().texture().size();
         (extent.width, extent.height)
     }
 
+    /// Clear color that will be applied when the next render pass begins.
+    fn pending_clear(&mut self) -> &mut Option<wgpu::Color>;
+    /// Whether depth and stencil buffers have not been cleared yet and will be cleared by the next render pass.
+    ///
+    /// It is set when the target (e.g. a frame) is created, so stale values are never tested against.
+    fn pending_depth_clear(&mut self) -> &mut bool;
+
     fn clear(&mut self, color: impl Color) {
-        let clear_color = {
-            let Rgba { r, g, b, a } = color.to_rgba().map(|c| c as f64);
-            wgpu::Color { r, g, b, a }
-        };
+        let Rgba { r, g, b, a } = color.to_rgba().map(|c| c as f64);
+        *self.pending_clear() = Some(wgpu::Color { r, g, b, a });
+    }
 
-        let view = &self.view().clone();
-        let _ = self
-            .encoder()
+    /// Begins a render pass into the target, applying pending clear if any.
+    ///
+    /// Clearing also resets depth and stencil buffers, they are also reset by the first pass into the target.
+    fn begin_pass(&mut self) -> wgpu::RenderPass<'_> {
+        let clear = self.pending_clear().take();
+        let clear_depth = std::mem::take(self.pending_depth_clear()) || clear.is_some();
+        let (view, resolve_target) = match self.multisample_view() {
+            Some(multisample) => (multisample.clone(), Some(self.view().clone())),
+            None => (self.view().clone(), None),
+        };
+        let depth_view = self.depth_view().cloned();
+        let depth_stencil_attachment =
+            depth_view
+                .as_ref()
+                .map(|view| wgpu::RenderPassDepthStencilAttachment {
+                    view,
+                    depth_ops: view.texture().format().has_depth_aspect().then(|| {
+                        wgpu::Operations {
+                            load: load_op(clear_depth, 1.0),
+                            store: wgpu::StoreOp::Store,
+                        }
+                    }),
+                    stencil_ops: view.texture().format().has_stencil_aspect().then(|| {
+                        wgpu::Operations {
+                            load: load_op(clear_depth, 0),
+                            store: wgpu::StoreOp::Store,
+                        }
+                    }),
+                });
+        let profiler = self.profiler().clone();
+        profiler.record_pass();
+        self.encoder()
             .begin_render_pass(&wgpu::RenderPassDescriptor {
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
-                    resolve_target: None,
+                    view: &view,
+                    resolve_target: resolve_target.as_ref(),
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(clear_color),
+                        load: clear.map_or(wgpu::LoadOp::Load, wgpu::LoadOp::Clear),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
                 })],
+                depth_stencil_attachment,
+                timestamp_writes: profiler.pass_timestamps(),
                 ..Default::default()
-            });
+            })
+    }
+
+    /// Records pending clear, if it has not been applied by any render pass yet.
+    fn flush(&mut self) {
+        if self.pending_clear().is_some() {
+            let _ = self.begin_pass();
+        }
     }
 
     fn render<C: Context, R: Renderer<C> + ?Sized>(&mut self, ctx: &C, renderer: &R) {
-        let view = &self.view().clone();
-        let mut pass = self
-            .encoder()
-            .begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load,
-                        store: wgpu::StoreOp::Store,
-                    },
-                    depth_slice: None,
-                })],
-                ..Default::default()
-            });
-        renderer.render(ctx, &mut pass);
+        self.render_iter(ctx, iter::once(renderer));
     }
+    /// Renders all `renderers` in a single render pass.
     fn render_iter<'r, C: Context, I: Iterator<Item = &'r R>, R: Renderer<C> + ?Sized + 'r>(
         &mut self,
         ctx: &C,
         renderers: I,
     ) {
+        let size = self.size();
+        let view_size = {
+            let extent = self.view().texture().size();
+            (extent.width, extent.height)
+        };
+        let viewport = self.viewport_rect();
+        let area = viewport.unwrap_or(ClipRect::new((0, 0), size));
+        if area.is_empty() {
+            self.flush();
+            return;
+        }
+        let profiler = self.profiler().clone();
+        let _active = self.state().profiler().activate(&profiler);
+        let mut pass = self.begin_pass();
+        if let Some(rect) = viewport {
+            pass.set_viewport(
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+                0.0,
+                1.0,
+            );
+        }
+        let mut current_scissor = None;
+        let mut current_reference = 0;
         for renderer in renderers {
-            self.render(ctx, renderer);
+            // Scissor is relative to the viewport.
+            let scissor = renderer
+                .scissor()
+                .map(|rect| rect.clamp(size).offset((area.x, area.y)).intersect(&area));
+            if scissor.is_some_and(|rect| rect.is_empty()) {
+                continue;
+            }
+            if scissor != current_scissor {
+                let rect = scissor.unwrap_or(area);
+                pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+                current_scissor = scissor;
+            }
+            let reference = renderer.stencil_reference();
+            if reference != current_reference {
+                pass.set_stencil_reference(reference);
+                current_reference = reference;
+            }
+            profiler.record_batch();
+            renderer.render(ctx, &mut pass);
+        }
+        // Some backends apply scissor to multisample resolve, so it must be reset before the pass ends.
+        if current_scissor.is_some() {
+            pass.set_scissor_rect(0, 0, view_size.0, view_size.1);
+        }
+        if viewport.is_some() {
+            pass.set_viewport(0.0, 0.0, view_size.0 as f32, view_size.1 as f32, 0.0, 1.0);
         }
     }
 
@@ -78,8 +179,33 @@ pub trait Target {
         Camera::new(self.state(), view)
     }
 
+    /// Records compute `pipeline` dispatch, it runs before render passes that are recorded after it.
+    fn compute(
+        &mut self,
+        pipeline: &ComputePipeline,
+        bind_groups: &[&wgpu::BindGroup],
+        workgroups: (u32, u32, u32),
+    ) {
+        pipeline.dispatch(self.encoder(), bind_groups, workgroups);
+    }
+
+    /// Target that renders into the `rect` of this target.
+    ///
+    /// Cameras of the viewport cover only the `rect`.
+    fn viewport(&mut self, rect: ClipRect) -> Viewport<'_, Self> {
+        Viewport::new(self, rect)
+    }
+
     fn scene(&mut self) -> AutoScene<'_, Self> {
         let camera = self.camera();
         AutoScene::new(self, camera)
     }
 }
+
+fn load_op<V>(clear: bool, value: V) -> wgpu::LoadOp<V> {
+    if clear {
+        wgpu::LoadOp::Clear(value)
+    } else {
+        wgpu::LoadOp::Load
+    }
+}
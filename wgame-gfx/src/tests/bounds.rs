@@ -0,0 +1,73 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{Affine2, Affine3A, Mat4, Vec2, Vec3};
+
+use crate::Bounds;
+
+fn unit() -> Bounds {
+    Bounds::new(Vec3::ZERO, Vec3::ONE)
+}
+
+fn assert_bounds_eq(a: Bounds, b: Bounds) {
+    assert!(
+        a.min.abs_diff_eq(b.min, 1e-5) && a.max.abs_diff_eq(b.max, 1e-5),
+        "{a:?} != {b:?}"
+    );
+}
+
+#[test]
+fn transform() {
+    assert_bounds_eq(
+        unit().transform(Affine3A::from_scale_rotation_translation(
+            Vec3::new(2.0, 3.0, 1.0),
+            Default::default(),
+            Vec3::new(-1.0, 0.0, 5.0),
+        )),
+        Bounds::new(Vec3::new(-1.0, 0.0, 5.0), Vec3::new(1.0, 3.0, 6.0)),
+    );
+    // Rotated box is covered by the box around all its corners.
+    assert_bounds_eq(
+        unit().transform(Affine2::from_angle(FRAC_PI_2)),
+        Bounds::new(Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 1.0)),
+    );
+    assert_bounds_eq(
+        unit().transform(Affine2::from_angle_translation(FRAC_PI_2 / 2.0, Vec2::X)),
+        Bounds::new(
+            Vec3::new(1.0 - 0.5f32.sqrt(), 0.0, 0.0),
+            Vec3::new(1.0 + 0.5f32.sqrt(), 2.0f32.sqrt(), 1.0),
+        ),
+    );
+}
+
+#[test]
+fn visible_orthographic() {
+    let view = Mat4::orthographic_rh(-2.0, 2.0, -1.0, 1.0, -1.0, 1.0);
+    let at = |x: f32, y: f32| Bounds::new(Vec3::new(x, y, 0.0), Vec3::new(x + 0.5, y + 0.5, 0.0));
+    assert!(at(0.0, 0.0).is_visible(view));
+    // Intersecting the edges.
+    assert!(at(1.75, 0.0).is_visible(view));
+    assert!(at(-2.25, -1.25).is_visible(view));
+    // Outside of the sides.
+    assert!(!at(2.25, 0.0).is_visible(view));
+    assert!(!at(-2.75, 0.0).is_visible(view));
+    assert!(!at(0.0, 1.25).is_visible(view));
+    assert!(!at(0.0, -1.75).is_visible(view));
+    // Outside of the near and far planes.
+    assert!(!Bounds::new(Vec3::new(0.0, 0.0, 1.5), Vec3::new(1.0, 1.0, 2.0)).is_visible(view));
+    assert!(!Bounds::new(Vec3::new(0.0, 0.0, -2.0), Vec3::new(1.0, 1.0, -1.5)).is_visible(view));
+    // Larger than the whole volume.
+    assert!(Bounds::new(Vec3::splat(-10.0), Vec3::splat(10.0)).is_visible(view));
+}
+
+#[test]
+fn visible_perspective() {
+    let view = Mat4::perspective_rh(FRAC_PI_2, 1.0, 0.1, 100.0);
+    let box_at = |center: Vec3| Bounds::new(center - 0.5, center + 0.5);
+    assert!(box_at(Vec3::new(0.0, 0.0, -5.0)).is_visible(view));
+    // Camera looks towards negative Z.
+    assert!(!box_at(Vec3::new(0.0, 0.0, 5.0)).is_visible(view));
+    assert!(!box_at(Vec3::new(0.0, 0.0, -200.0)).is_visible(view));
+    // Field of view is 90 degrees, so the visible width at distance 5 is 10.
+    assert!(box_at(Vec3::new(5.0, 0.0, -5.0)).is_visible(view));
+    assert!(!box_at(Vec3::new(7.0, 0.0, -5.0)).is_visible(view));
+}
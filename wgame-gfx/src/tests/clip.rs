@@ -0,0 +1,49 @@
+use crate::ClipRect;
+
+#[test]
+fn intersect() {
+    let a = ClipRect::new((10, 20), (100, 50));
+    let b = ClipRect::new((50, 0), (100, 40));
+    assert_eq!(a.intersect(&b), ClipRect::new((50, 20), (60, 20)));
+    assert_eq!(a.intersect(&b), b.intersect(&a));
+    assert_eq!(a.intersect(&a), a);
+}
+
+#[test]
+fn intersect_disjoint() {
+    let a = ClipRect::new((0, 0), (10, 10));
+    let b = ClipRect::new((20, 30), (10, 10));
+    let c = a.intersect(&b);
+    assert!(c.is_empty());
+    // Empty rectangle stays inside of both rectangles' bounds.
+    assert_eq!(c, ClipRect::new((10, 10), (0, 0)));
+}
+
+#[test]
+fn intersect_overflow() {
+    let a = ClipRect::new((u32::MAX - 10, 0), (100, 10));
+    let b = ClipRect::new((0, 0), (u32::MAX, 5));
+    assert_eq!(a.intersect(&b), ClipRect::new((u32::MAX - 10, 0), (10, 5)));
+}
+
+#[test]
+fn clamp() {
+    let size = (800, 600);
+    assert_eq!(
+        ClipRect::new((700, 500), (200, 200)).clamp(size),
+        ClipRect::new((700, 500), (100, 100))
+    );
+    let outside = ClipRect::new((2000, 100), (50, 50)).clamp(size);
+    assert!(outside.is_empty());
+    assert!(outside.x <= size.0 && outside.y <= size.1);
+}
+
+#[test]
+fn offset() {
+    let a = ClipRect::new((10, 20), (30, 40));
+    assert_eq!(a.offset((5, 6)), ClipRect::new((15, 26), (30, 40)));
+    assert_eq!(
+        a.offset((u32::MAX, 0)),
+        ClipRect::new((u32::MAX, 20), (30, 40))
+    );
+}
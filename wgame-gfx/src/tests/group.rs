@@ -0,0 +1,59 @@
+use glam::{Affine3A, Vec3};
+use rgb::Rgba;
+
+use super::mock::{TestInstance, batches, test_storage};
+use crate::{Batching, Group, Scene, modifiers::Colorable};
+
+#[test]
+fn nested() {
+    let scale = Affine3A::from_scale(Vec3::splat(2.0));
+    let translation = Affine3A::from_translation(Vec3::new(1.0, 0.0, 0.0));
+    let inner = Group {
+        xform: scale,
+        color: Rgba::new(0.5, 1.0, 1.0, 1.0),
+        ..Group::new(vec![
+            TestInstance::new(1, 'a', None),
+            TestInstance::new(2, 'a', None).multiply_color(Rgba::new(1.0, 1.0, 0.5, 1.0)),
+        ])
+    };
+    let outer = Group {
+        xform: translation,
+        color: Rgba::new(1.0, 0.5, 1.0, 1.0),
+        ..Group::new((inner, TestInstance::new(0, 'b', None)))
+    };
+
+    let mut scene = Scene::new(Batching::Submission);
+    scene.add(&outer);
+    assert_eq!(batches(scene.iter()), [('a', vec![1, 2]), ('b', vec![0])]);
+
+    let attributes = scene
+        .iter()
+        .flat_map(|storage| test_storage(storage).attributes.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attributes,
+        [
+            (translation * scale, Rgba::new(0.5, 0.5, 1.0, 1.0)),
+            (translation * scale, Rgba::new(0.5, 0.5, 0.5, 1.0)),
+            (translation, Rgba::new(1.0, 0.5, 1.0, 1.0)),
+        ]
+    );
+}
+
+#[test]
+fn order() {
+    let group = Group::new((
+        TestInstance::new(1, 'a', None),
+        Group::new([TestInstance::new(2, 'b', None).with_order(1)]),
+    ))
+    .with_order(-1);
+
+    let mut scene = Scene::new(Batching::ByResource);
+    scene.add(&TestInstance::new(0, 'a', None));
+    scene.add(&group);
+    // Group order applies before the order of its descendants.
+    assert_eq!(
+        batches(scene.iter()),
+        [('a', vec![1]), ('b', vec![2]), ('a', vec![0])]
+    );
+}
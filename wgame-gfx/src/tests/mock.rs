@@ -0,0 +1,155 @@
+//! Instances that record their identifiers instead of drawing anything.
+
+use std::any::Any;
+
+use glam::{Affine3A, Vec3};
+use rgb::Rgba;
+
+use crate::{
+    AnyStorage, Bounds, Context, Instance, Object, Ordered, Renderer, Resource, Storage,
+    impl_object_for_instance,
+    modifiers::{Colorable, Transformable},
+    types::{Color, Transform, color},
+};
+
+pub struct TestContext;
+
+impl Context for TestContext {
+    fn bind_group(&self) -> wgpu::BindGroup {
+        unreachable!("Test instances are never rendered")
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TestResource {
+    pub name: char,
+    pub order: i32,
+}
+
+impl Resource for TestResource {
+    fn order(&self) -> impl Iterator<Item = i32> {
+        [self.order].into_iter()
+    }
+}
+
+pub struct TestStorage {
+    resource: TestResource,
+    pub ids: Vec<u32>,
+    /// Transformation and color of each stored instance.
+    pub attributes: Vec<(Affine3A, Rgba<f32>)>,
+}
+
+impl Storage for TestStorage {
+    type Context = TestContext;
+    type Resource = TestResource;
+    type Renderer = TestRenderer;
+
+    fn resource(&self) -> Self::Resource {
+        self.resource.clone()
+    }
+    fn bake(&self) -> Self::Renderer {
+        TestRenderer
+    }
+}
+
+pub struct TestRenderer;
+
+impl Renderer<TestContext> for TestRenderer {
+    fn render(&self, _: &TestContext, _: &mut wgpu::RenderPass<'_>) {}
+}
+
+#[derive(Clone)]
+pub struct TestInstance {
+    pub id: u32,
+    pub resource: TestResource,
+    pub bounds: Option<Bounds>,
+    pub xform: Affine3A,
+    pub color: Rgba<f32>,
+}
+
+impl TestInstance {
+    /// Instance with unit square bounds at `pos` in XY plane.
+    pub fn new(id: u32, name: char, pos: Option<(f32, f32)>) -> Self {
+        Self {
+            id,
+            resource: TestResource { name, order: 0 },
+            bounds: pos
+                .map(|(x, y)| Bounds::new(Vec3::new(x, y, 0.0), Vec3::new(x + 1.0, y + 1.0, 0.0))),
+            xform: Affine3A::IDENTITY,
+            color: color::WHITE.to_rgba(),
+        }
+    }
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.resource.order = order;
+        self
+    }
+}
+
+impl Instance for TestInstance {
+    type Context = TestContext;
+    type Resource = TestResource;
+    type Storage = TestStorage;
+
+    fn resource(&self) -> Self::Resource {
+        self.resource.clone()
+    }
+    fn new_storage(&self) -> Self::Storage {
+        TestStorage {
+            resource: self.resource.clone(),
+            ids: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+    fn store(&self, storage: &mut Self::Storage) {
+        storage.ids.push(self.id);
+        storage.attributes.push((self.xform, self.color));
+    }
+    fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+}
+
+impl_object_for_instance!(TestInstance);
+
+impl Transformable for TestInstance {
+    fn transform<X: Transform>(&self, xform: X) -> Self {
+        Self {
+            xform: xform.to_affine3() * self.xform,
+            ..self.clone()
+        }
+    }
+}
+
+impl Colorable for TestInstance {
+    fn multiply_color<C: Color>(&self, color: C) -> Self {
+        Self {
+            color: self.color.mul(color),
+            ..self.clone()
+        }
+    }
+}
+
+/// Resource names and instance identifiers of batches in drawing order.
+pub fn batches<'a>(
+    storages: impl Iterator<Item = &'a dyn AnyStorage<TestContext>>,
+) -> Vec<(char, Vec<u32>)> {
+    storages
+        .map(|storage| {
+            let storage = test_storage(storage);
+            (storage.resource.name, storage.ids.clone())
+        })
+        .collect()
+}
+
+/// Test storage itself or the one wrapped in [`Ordered`].
+pub fn test_storage(storage: &dyn AnyStorage<TestContext>) -> &TestStorage {
+    let storage = storage as &dyn Any;
+    storage
+        .downcast_ref::<TestStorage>()
+        .or_else(|| {
+            storage
+                .downcast_ref::<Ordered<TestStorage>>()
+                .map(|ordered| &ordered.inner)
+        })
+        .unwrap()
+}
@@ -0,0 +1,8 @@
+mod bounds;
+mod clip;
+mod group;
+mod mock;
+mod readback;
+mod retained;
+mod scene;
+mod state;
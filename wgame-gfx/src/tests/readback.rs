@@ -0,0 +1,67 @@
+use rgb::Rgba;
+use wgpu::TextureFormat;
+
+use crate::readback::{padded_bytes_per_row, read_rows};
+
+/// Texture data with rows padded by `0xff` bytes, texel at `(x, y)` is `[x, y, x + y, 255]`.
+fn padded_data(width: u32, height: u32, bytes_per_row: u32) -> Vec<u8> {
+    let mut data = vec![0xff; (bytes_per_row * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let offset = (y * bytes_per_row + x * 4) as usize;
+            data[offset..offset + 4].copy_from_slice(&[x as u8, y as u8, (x + y) as u8, 255]);
+        }
+    }
+    data
+}
+
+#[test]
+fn bytes_per_row() {
+    assert_eq!(
+        padded_bytes_per_row(TextureFormat::Rgba8Unorm, 3).unwrap(),
+        256
+    );
+    assert_eq!(
+        padded_bytes_per_row(TextureFormat::Rgba8Unorm, 64).unwrap(),
+        256
+    );
+    assert_eq!(
+        padded_bytes_per_row(TextureFormat::Rgba8Unorm, 65).unwrap(),
+        512
+    );
+    assert_eq!(
+        padded_bytes_per_row(TextureFormat::Rgba16Float, 33).unwrap(),
+        512
+    );
+    assert!(padded_bytes_per_row(TextureFormat::Depth24Plus, 64).is_err());
+}
+
+#[test]
+fn padded_rows() {
+    for (width, height) in [(1, 1), (3, 2), (64, 2), (65, 3), (100, 4)] {
+        let bytes_per_row = padded_bytes_per_row(TextureFormat::Rgba8Unorm, width).unwrap();
+        let data = padded_data(width, height, bytes_per_row);
+        let pixels: Vec<Rgba<u8>> = read_rows(
+            TextureFormat::Rgba8Unorm,
+            (width, height),
+            bytes_per_row,
+            &data,
+        )
+        .unwrap();
+        let expected: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Rgba::new(x as u8, y as u8, (x + y) as u8, 255)))
+            .collect();
+        assert_eq!(pixels, expected, "{width}x{height}");
+    }
+}
+
+#[test]
+fn padded_rows_bgra() {
+    let (width, height) = (65, 2);
+    let data = padded_data(width, height, 512);
+    let pixels: Vec<Rgba<u8>> =
+        read_rows(TextureFormat::Bgra8Unorm, (width, height), 512, &data).unwrap();
+    assert_eq!(pixels.len(), 130);
+    assert_eq!(pixels[64], Rgba::new(64, 0, 64, 255));
+    assert_eq!(pixels[66], Rgba::new(2, 1, 1, 255));
+}
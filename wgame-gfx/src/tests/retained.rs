@@ -0,0 +1,110 @@
+use std::rc::Rc;
+
+use super::mock::{TestContext, TestInstance, TestResource};
+use crate::{InstanceVisitor, Object, RetainedScene};
+
+/// Object with instances of two resources.
+struct Pair(TestInstance, TestInstance);
+
+impl Object for Pair {
+    type Context = TestContext;
+    fn for_each_instance<V: InstanceVisitor<TestContext>>(&self, visitor: &mut V) {
+        visitor.visit(&self.0);
+        visitor.visit(&self.1);
+    }
+}
+
+fn instance(id: u32, name: char) -> TestInstance {
+    TestInstance::new(id, name, None)
+}
+
+/// Resource names of batches in drawing order.
+fn names(scene: &RetainedScene<TestContext>) -> String {
+    scene
+        .bake_all()
+        .into_iter()
+        .map(|(resource, _)| {
+            (resource.as_ref() as &dyn std::any::Any)
+                .downcast_ref::<TestResource>()
+                .unwrap()
+                .name
+        })
+        .collect()
+}
+
+#[test]
+fn insertion_order() {
+    let mut scene = RetainedScene::default();
+    for (id, name) in "dbeac".chars().enumerate() {
+        scene.add(instance(id as u32, name));
+    }
+    for _ in 0..4 {
+        assert_eq!(names(&scene), "dbeac");
+    }
+}
+
+#[test]
+fn resource_order() {
+    let mut scene = RetainedScene::default();
+    scene.add(instance(0, 'a').with_order(1));
+    scene.add(instance(1, 'b'));
+    scene.add(instance(2, 'c').with_order(-1));
+    assert_eq!(names(&scene), "cba");
+}
+
+#[test]
+fn update_keeps_position() {
+    let mut scene = RetainedScene::default();
+    let a = scene.add(instance(0, 'a'));
+    scene.add(instance(1, 'b'));
+    assert!(scene.update(a, instance(0, 'a')));
+    assert_eq!(names(&scene), "ab");
+
+    // The batch is removed when it becomes empty, so the new one goes last.
+    assert!(scene.update(a, instance(0, 'c')));
+    assert_eq!(names(&scene), "bc");
+    assert_eq!(scene.len(), 2);
+}
+
+#[test]
+fn update_rebakes_changed_batches() {
+    let mut scene = RetainedScene::default();
+    let a = scene.add(Pair(instance(0, 'a'), instance(0, 'b')));
+    scene.add(instance(1, 'b'));
+    scene.add(instance(2, 'c'));
+    let before = scene.bake_all();
+
+    assert!(scene.update(a, Pair(instance(0, 'a'), instance(0, 'd'))));
+    let after = scene.bake_all();
+    assert_eq!(names(&scene), "abcd");
+    // `a` and `b` contained the old object, `d` is new, `c` is untouched.
+    let rebaked: Vec<_> = before
+        .iter()
+        .zip(&after)
+        .map(|((_, x), (_, y))| !Rc::ptr_eq(x, y))
+        .collect();
+    assert_eq!(rebaked, [true, true, false]);
+}
+
+#[test]
+fn remove() {
+    let mut scene = RetainedScene::default();
+    let a = scene.add(instance(0, 'a'));
+    let b = scene.add(Pair(instance(1, 'a'), instance(1, 'b')));
+    let c = scene.add(instance(2, 'c'));
+    let before = scene.bake_all();
+
+    assert!(scene.remove(b));
+    assert!(!scene.remove(b));
+    assert!(!scene.update(b, instance(1, 'b')));
+    assert_eq!(names(&scene), "ac");
+    let after = scene.bake_all();
+    assert!(!Rc::ptr_eq(&before[0].1, &after[0].1));
+    assert!(Rc::ptr_eq(&before[2].1, &after[1].1));
+
+    assert!(scene.remove(a));
+    assert_eq!(names(&scene), "c");
+    assert!(!scene.contains(a));
+    assert!(scene.contains(c));
+    assert_eq!(scene.len(), 1);
+}
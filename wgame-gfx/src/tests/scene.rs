@@ -0,0 +1,110 @@
+use super::mock::{TestInstance, batches};
+use crate::{Batching, Scene};
+
+fn scene(batching: Batching, instances: &[TestInstance]) -> Scene<super::mock::TestContext> {
+    let mut scene = Scene::new(batching);
+    for instance in instances {
+        scene.add(instance);
+    }
+    scene
+}
+
+#[test]
+fn by_resource() {
+    let scene = scene(
+        Batching::ByResource,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))),
+            TestInstance::new(1, 'b', Some((0.0, 0.0))),
+            TestInstance::new(2, 'a', Some((0.0, 0.0))),
+        ],
+    );
+    assert_eq!(batches(scene.iter()), [('a', vec![0, 2]), ('b', vec![1])]);
+}
+
+#[test]
+fn submission_disjoint() {
+    let scene = scene(
+        Batching::Submission,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))),
+            TestInstance::new(1, 'b', Some((2.0, 0.0))),
+            TestInstance::new(2, 'a', Some((0.0, 2.0))),
+            TestInstance::new(3, 'b', Some((2.0, 2.0))),
+        ],
+    );
+    assert_eq!(
+        batches(scene.iter()),
+        [('a', vec![0, 2]), ('b', vec![1, 3])]
+    );
+}
+
+#[test]
+fn submission_overlapping() {
+    let scene = scene(
+        Batching::Submission,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))),
+            TestInstance::new(1, 'b', Some((0.5, 0.5))),
+            TestInstance::new(2, 'a', Some((1.0, 1.0))),
+            // Does not overlap the last batch, but it overlaps `b` that is drawn after the first batch.
+            TestInstance::new(3, 'a', Some((0.0, 0.5))),
+        ],
+    );
+    assert_eq!(
+        batches(scene.iter()),
+        [('a', vec![0]), ('b', vec![1]), ('a', vec![2, 3])]
+    );
+}
+
+#[test]
+fn submission_merged_bounds() {
+    let scene = scene(
+        Batching::Submission,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))),
+            TestInstance::new(1, 'b', Some((4.0, 0.0))),
+            // Overlaps `b`, so the bounds of `b` batch grow.
+            TestInstance::new(2, 'b', Some((0.5, 0.5))),
+            TestInstance::new(3, 'a', Some((0.0, 1.0))),
+        ],
+    );
+    assert_eq!(
+        batches(scene.iter()),
+        [('a', vec![0]), ('b', vec![1, 2]), ('a', vec![3])]
+    );
+}
+
+#[test]
+fn submission_unbounded() {
+    let scene = scene(
+        Batching::Submission,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))),
+            TestInstance::new(1, 'b', None),
+            TestInstance::new(2, 'a', Some((8.0, 8.0))),
+            TestInstance::new(3, 'a', None),
+        ],
+    );
+    assert_eq!(
+        batches(scene.iter()),
+        [('a', vec![0]), ('b', vec![1]), ('a', vec![2, 3])]
+    );
+}
+
+#[test]
+fn submission_order() {
+    let scene = scene(
+        Batching::Submission,
+        &[
+            TestInstance::new(0, 'a', Some((0.0, 0.0))).with_order(1),
+            TestInstance::new(1, 'b', Some((0.0, 0.0))),
+            TestInstance::new(2, 'a', Some((0.0, 0.0))).with_order(1),
+        ],
+    );
+    // Batch with greater order is drawn last, the one with equal order is split.
+    assert_eq!(
+        batches(scene.iter()),
+        [('b', vec![1]), ('a', vec![0]), ('a', vec![2])]
+    );
+}
@@ -0,0 +1,11 @@
+use crate::{BufferPool, Graphics, Profiler, TexturePool};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn send_sync() {
+    assert_send_sync::<Graphics>();
+    assert_send_sync::<BufferPool>();
+    assert_send_sync::<TexturePool>();
+    assert_send_sync::<Profiler>();
+}
@@ -0,0 +1,199 @@
+//! Colors are passed to shaders unchanged, so they are interpreted in the color space of the graphics,
+//! see [`ColorSpace`](crate::ColorSpace).
+//!
+//! Constants below have the same values both in sRGB and in linear space.
+//! Other colors that are given in sRGB (e.g. picked in an image editor) should be converted
+//! with [`Color::to_color_space`] into the color space of the graphics.
+//!
+//! The same applies to [`hex`] and [`parse`]d colors, and to conversions from and to
+//! [`Hsv`], [`Hsl`], [`Oklab`] and [`Oklch`], which treat RGB as sRGB-encoded.
+
+mod named;
+mod parse;
+mod spaces;
+#[cfg(test)]
+mod tests;
+
+use glam::{Vec3, Vec4};
+use half::f16;
+use rgb::{Rgb, Rgba};
+
+use crate::ColorSpace;
+
+pub use self::{
+    named::{NAMED_COLORS, named},
+    parse::parse,
+    spaces::{Hsl, Hsv, Oklab, Oklch},
+};
+
+/// Creates sRGB-encoded color from `0xRRGGBB` value.
+pub const fn hex(value: u32) -> Rgb<f32> {
+    Rgb::new(
+        ((value >> 16) & 0xff) as f32 / 255.0,
+        ((value >> 8) & 0xff) as f32 / 255.0,
+        (value & 0xff) as f32 / 255.0,
+    )
+}
+
+/// Decodes sRGB-encoded color component into linear one.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes linear color component into sRGB one.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// `#000000`
+pub const BLACK: Rgb<f32> = Rgb::new(0.0, 0.0, 0.0);
+/// `#ff0000`
+pub const RED: Rgb<f32> = Rgb::new(1.0, 0.0, 0.0);
+/// `#ffff00`
+pub const YELLOW: Rgb<f32> = Rgb::new(1.0, 1.0, 0.0);
+/// `#00ff00`
+pub const GREEN: Rgb<f32> = Rgb::new(0.0, 1.0, 0.0);
+/// `#00ffff`
+pub const CYAN: Rgb<f32> = Rgb::new(0.0, 1.0, 1.0);
+/// `#0000ff`
+pub const BLUE: Rgb<f32> = Rgb::new(0.0, 0.0, 1.0);
+/// `#ff00ff`
+pub const MAGENTA: Rgb<f32> = Rgb::new(1.0, 0.0, 1.0);
+/// `#ffffff`
+pub const WHITE: Rgb<f32> = Rgb::new(1.0, 1.0, 1.0);
+
+pub trait Color: Copy {
+    fn to_rgba(self) -> Rgba<f32>;
+
+    fn to_rgba_f16(self) -> Rgba<f16> {
+        let this = self.to_rgba();
+        Rgba::new(
+            f16::from_f32(this.r),
+            f16::from_f32(this.g),
+            f16::from_f32(this.b),
+            f16::from_f32(this.a),
+        )
+    }
+
+    fn to_vec4(self) -> Vec4 {
+        let c = self.to_rgba();
+        Vec4::new(c.r, c.g, c.b, c.a)
+    }
+
+    fn mul(self, other: impl Color) -> Rgba<f32> {
+        (self.to_vec4() * other.to_vec4()).to_rgba()
+    }
+
+    fn mix(self, other: impl Color, other_weight: f32) -> Rgba<f32> {
+        (self.to_vec4() * (1.0 - other_weight) + other.to_vec4() * other_weight).to_rgba()
+    }
+
+    /// Perceptually uniform interpolation, performed in [`Oklab`] space.
+    fn mix_oklab(self, other: impl Color, other_weight: f32) -> Rgba<f32> {
+        (self.to_oklab())
+            .mix(other.to_oklab(), other_weight)
+            .to_rgba()
+    }
+
+    fn to_hsv(self) -> Hsv {
+        Hsv::from_color(self)
+    }
+    fn to_hsl(self) -> Hsl {
+        Hsl::from_color(self)
+    }
+    fn to_oklab(self) -> Oklab {
+        Oklab::from_color(self)
+    }
+    fn to_oklch(self) -> Oklch {
+        Oklch::from_color(self)
+    }
+
+    /// Treats the color as sRGB-encoded and converts it to linear space. Alpha is left unchanged.
+    fn srgb_to_linear(self) -> Rgba<f32> {
+        let Rgba { r, g, b, a } = self.to_rgba();
+        Rgba::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    /// Treats the color as linear and encodes it into sRGB. Alpha is left unchanged.
+    fn linear_to_srgb(self) -> Rgba<f32> {
+        let Rgba { r, g, b, a } = self.to_rgba();
+        Rgba::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+    }
+
+    /// Treats the color as sRGB-encoded and converts it into the `color_space` of rendering.
+    ///
+    /// Use it for [`hex`], [`parse`]d and [`named`] colors, e.g. `hex(0x336699).to_color_space(state.color_space())`.
+    fn to_color_space(self, color_space: ColorSpace) -> Rgba<f32> {
+        match color_space {
+            ColorSpace::Srgb => self.to_rgba(),
+            ColorSpace::Linear => self.srgb_to_linear(),
+        }
+    }
+}
+
+impl Color for Rgb<f32> {
+    fn to_rgba(self) -> Rgba<f32> {
+        Rgba::new(self.r, self.g, self.b, 1.0)
+    }
+}
+
+impl Color for Rgba<f32> {
+    fn to_rgba(self) -> Rgba<f32> {
+        self
+    }
+}
+
+impl Color for Rgb<f16> {
+    fn to_rgba(self) -> Rgba<f32> {
+        Rgba::new(self.r.to_f32(), self.g.to_f32(), self.b.to_f32(), 1.0)
+    }
+}
+
+impl Color for Rgba<f16> {
+    fn to_rgba(self) -> Rgba<f32> {
+        Rgba::new(
+            self.r.to_f32(),
+            self.g.to_f32(),
+            self.b.to_f32(),
+            self.a.to_f32(),
+        )
+    }
+}
+
+impl Color for Vec3 {
+    fn to_rgba(self) -> Rgba<f32> {
+        Rgba {
+            r: self.x,
+            g: self.y,
+            b: self.z,
+            a: 1.0,
+        }
+    }
+
+    fn to_vec4(self) -> Vec4 {
+        Vec4::from((self, 1.0))
+    }
+}
+
+impl Color for Vec4 {
+    fn to_rgba(self) -> Rgba<f32> {
+        Rgba {
+            r: self.x,
+            g: self.y,
+            b: self.z,
+            a: self.w,
+        }
+    }
+
+    fn to_vec4(self) -> Vec4 {
+        self
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context as _, Result, bail};
+use rgb::Rgba;
+
+use super::{Color, Hsl, Oklab, Oklch, named};
+
+/// Parses color from a hex string or CSS color function.
+///
+/// Supported formats:
+/// + `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`,
+/// + `rgb()`, `rgba()`, `hsl()`, `hsla()`, `oklab()` and `oklch()`, with either comma- or space-separated arguments,
+/// + CSS named colors and `transparent`.
+///
+/// Resulting color is sRGB-encoded.
+pub fn parse(text: &str) -> Result<Rgba<f32>> {
+    let text = text.trim();
+    if let Some(digits) = text.strip_prefix('#') {
+        return parse_hex(digits).with_context(|| format!("Invalid hex color: {text:?}"));
+    }
+    if let Some((name, args)) = text.split_once('(') {
+        let args = args
+            .strip_suffix(')')
+            .with_context(|| format!("Missing closing parenthesis: {text:?}"))?;
+        return parse_function(&name.trim().to_ascii_lowercase(), args)
+            .with_context(|| format!("Invalid color function: {text:?}"));
+    }
+    if text.eq_ignore_ascii_case("transparent") {
+        return Ok(Rgba::new(0.0, 0.0, 0.0, 0.0));
+    }
+    named(text)
+        .map(Color::to_rgba)
+        .with_context(|| format!("Unknown color: {text:?}"))
+}
+
+fn parse_hex(digits: &str) -> Result<Rgba<f32>> {
+    if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Non-hex digit found");
+    }
+    let value = |s: &str| u8::from_str_radix(s, 16).map(|x| x as f32 / 255.0);
+    let short = |i: usize| value(&digits[i..=i].repeat(2));
+    let long = |i: usize| value(&digits[2 * i..2 * i + 2]);
+    Ok(match digits.len() {
+        3 => Rgba::new(short(0)?, short(1)?, short(2)?, 1.0),
+        4 => Rgba::new(short(0)?, short(1)?, short(2)?, short(3)?),
+        6 => Rgba::new(long(0)?, long(1)?, long(2)?, 1.0),
+        8 => Rgba::new(long(0)?, long(1)?, long(2)?, long(3)?),
+        n => bail!("Expected 3, 4, 6 or 8 digits, got {n}"),
+    })
+}
+
+fn parse_function(name: &str, args: &str) -> Result<Rgba<f32>> {
+    let args = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>();
+    let (args, alpha) = match args.as_slice() {
+        [x, y, z] => ([*x, *y, *z], 1.0),
+        [x, y, z, alpha] => ([*x, *y, *z], parse_number(alpha, 1.0)?),
+        _ => bail!("Expected 3 or 4 arguments, got {}", args.len()),
+    };
+    let [x, y, z] = args;
+    Ok(match name {
+        "rgb" | "rgba" => Rgba::new(
+            parse_number(x, 255.0)? / 255.0,
+            parse_number(y, 255.0)? / 255.0,
+            parse_number(z, 255.0)? / 255.0,
+            alpha,
+        ),
+        "hsl" | "hsla" => Hsl::with_alpha(
+            parse_angle(x)?,
+            parse_number(y, 100.0)? / 100.0,
+            parse_number(z, 100.0)? / 100.0,
+            alpha,
+        )
+        .to_rgba(),
+        "oklab" => Oklab::with_alpha(
+            parse_number(x, 1.0)?,
+            parse_number(y, 0.4)?,
+            parse_number(z, 0.4)?,
+            alpha,
+        )
+        .to_rgba(),
+        "oklch" => Oklch::with_alpha(
+            parse_number(x, 1.0)?,
+            parse_number(y, 0.4)?,
+            parse_angle(z)?,
+            alpha,
+        )
+        .to_rgba(),
+        other => bail!("Unknown function {other:?}"),
+    })
+}
+
+/// Parses number or percentage of `full` value.
+fn parse_number(arg: &str, full: f32) -> Result<f32> {
+    Ok(match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>()? / 100.0 * full,
+        None => arg.parse()?,
+    })
+}
+
+/// Parses angle in degrees.
+fn parse_angle(arg: &str) -> Result<f32> {
+    let units = [
+        ("deg", 1.0),
+        ("grad", 0.9),
+        ("rad", 180.0 / std::f32::consts::PI),
+        ("turn", 360.0),
+    ];
+    for (suffix, scale) in units {
+        if let Some(value) = arg.strip_suffix(suffix) {
+            return Ok(value.parse::<f32>()? * scale);
+        }
+    }
+    Ok(arg.parse()?)
+}
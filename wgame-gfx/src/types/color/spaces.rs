@@ -0,0 +1,234 @@
+// Oklab coefficients are kept as in the reference implementation.
+#![allow(clippy::excessive_precision)]
+
+use rgb::Rgba;
+
+use super::{Color, linear_to_srgb};
+
+/// Hue, saturation and value.
+///
+/// Hue is in degrees, other components are in `0.0..=1.0`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+    pub alpha: f32,
+}
+
+/// Hue, saturation and lightness.
+///
+/// Hue is in degrees, other components are in `0.0..=1.0`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+    pub alpha: f32,
+}
+
+/// Perceptually uniform color space, see <https://bottosson.github.io/posts/oklab/>.
+///
+/// Lightness is in `0.0..=1.0`, `a` and `b` are roughly in `-0.4..=0.4`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+    pub alpha: f32,
+}
+
+/// Polar form of [`Oklab`]: lightness, chroma and hue in degrees.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+    pub alpha: f32,
+}
+
+impl Hsv {
+    pub const fn new(h: f32, s: f32, v: f32) -> Self {
+        Self::with_alpha(h, s, v, 1.0)
+    }
+    pub const fn with_alpha(h: f32, s: f32, v: f32, alpha: f32) -> Self {
+        Self { h, s, v, alpha }
+    }
+
+    pub fn from_color(color: impl Color) -> Self {
+        let Rgba { r, g, b, a } = color.to_rgba();
+        let (h, min, max) = hue(r, g, b);
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        Self::with_alpha(h, s, max, a)
+    }
+}
+
+impl Hsl {
+    pub const fn new(h: f32, s: f32, l: f32) -> Self {
+        Self::with_alpha(h, s, l, 1.0)
+    }
+    pub const fn with_alpha(h: f32, s: f32, l: f32, alpha: f32) -> Self {
+        Self { h, s, l, alpha }
+    }
+
+    pub fn from_color(color: impl Color) -> Self {
+        let Rgba { r, g, b, a } = color.to_rgba();
+        let (h, min, max) = hue(r, g, b);
+        let l = 0.5 * (max + min);
+        let s = if l > 0.0 && l < 1.0 {
+            (max - l) / l.min(1.0 - l)
+        } else {
+            0.0
+        };
+        Self::with_alpha(h, s, l, a)
+    }
+}
+
+impl Oklab {
+    pub const fn new(l: f32, a: f32, b: f32) -> Self {
+        Self::with_alpha(l, a, b, 1.0)
+    }
+    pub const fn with_alpha(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        Self { l, a, b, alpha }
+    }
+
+    pub fn from_color(color: impl Color) -> Self {
+        let Rgba { r, g, b, a: alpha } = color.srgb_to_linear();
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Self::with_alpha(
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+            alpha,
+        )
+    }
+
+    /// Interpolates between colors, `weight` is the weight of `other`.
+    pub fn mix(self, other: Self, weight: f32) -> Self {
+        let lerp = |x: f32, y: f32| x + (y - x) * weight;
+        Self::with_alpha(
+            lerp(self.l, other.l),
+            lerp(self.a, other.a),
+            lerp(self.b, other.b),
+            lerp(self.alpha, other.alpha),
+        )
+    }
+}
+
+impl Oklch {
+    pub const fn new(l: f32, c: f32, h: f32) -> Self {
+        Self::with_alpha(l, c, h, 1.0)
+    }
+    pub const fn with_alpha(l: f32, c: f32, h: f32, alpha: f32) -> Self {
+        Self { l, c, h, alpha }
+    }
+
+    pub fn from_color(color: impl Color) -> Self {
+        Oklab::from_color(color).into()
+    }
+
+    /// Interpolates between colors along the shorter hue arc, `weight` is the weight of `other`.
+    pub fn mix(self, other: Self, weight: f32) -> Self {
+        let lerp = |x: f32, y: f32| x + (y - x) * weight;
+        let dh = (other.h - self.h + 180.0).rem_euclid(360.0) - 180.0;
+        Self::with_alpha(
+            lerp(self.l, other.l),
+            lerp(self.c, other.c),
+            (self.h + dh * weight).rem_euclid(360.0),
+            lerp(self.alpha, other.alpha),
+        )
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(Oklab { l, a, b, alpha }: Oklab) -> Self {
+        Self::with_alpha(
+            l,
+            a.hypot(b),
+            b.atan2(a).to_degrees().rem_euclid(360.0),
+            alpha,
+        )
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(Oklch { l, c, h, alpha }: Oklch) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+        Self::with_alpha(l, c * cos, c * sin, alpha)
+    }
+}
+
+impl From<Hsv> for Hsl {
+    fn from(color: Hsv) -> Self {
+        Self::from_color(color)
+    }
+}
+
+impl From<Hsl> for Hsv {
+    fn from(color: Hsl) -> Self {
+        Self::from_color(color)
+    }
+}
+
+impl Color for Hsv {
+    fn to_rgba(self) -> Rgba<f32> {
+        let Self { h, s, v, alpha } = self;
+        let f = |n: f32| {
+            let k = (n + h / 60.0).rem_euclid(6.0);
+            v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Rgba::new(f(5.0), f(3.0), f(1.0), alpha)
+    }
+}
+
+impl Color for Hsl {
+    fn to_rgba(self) -> Rgba<f32> {
+        let Self { h, s, l, alpha } = self;
+        let f = |n: f32| {
+            let k = (n + h / 30.0).rem_euclid(12.0);
+            l - s * l.min(1.0 - l) * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Rgba::new(f(0.0), f(8.0), f(4.0), alpha)
+    }
+}
+
+impl Color for Oklab {
+    fn to_rgba(self) -> Rgba<f32> {
+        let Self { l, a, b, alpha } = self;
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        let encode = |x: f32| linear_to_srgb(x.clamp(0.0, 1.0));
+        Rgba::new(
+            encode(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+            encode(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+            encode(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+            alpha,
+        )
+    }
+}
+
+impl Color for Oklch {
+    fn to_rgba(self) -> Rgba<f32> {
+        Oklab::from(self).to_rgba()
+    }
+}
+
+/// Returns hue in degrees with minimal and maximal components.
+fn hue(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (h, min, max)
+}
@@ -0,0 +1,98 @@
+use rgb::{Rgb, Rgba};
+
+use super::*;
+use crate::ColorSpace;
+
+fn assert_close(a: impl Color, b: impl Color) {
+    let (a, b) = (a.to_vec4(), b.to_vec4());
+    assert!(a.abs_diff_eq(b, 1e-3), "{a} != {b}");
+}
+
+#[test]
+fn hex_and_named() {
+    assert_eq!(hex(0xff0000), RED);
+    assert_eq!(named("Lime"), Some(GREEN));
+    assert_eq!(named("green"), Some(hex(0x008000)));
+    assert_eq!(named("unknown"), None);
+    assert!(NAMED_COLORS.is_sorted_by_key(|(name, _)| *name));
+}
+
+#[test]
+fn parse_hex() {
+    assert_close(parse("#f80").unwrap(), hex(0xff8800));
+    assert_close(
+        parse("#ff880080").unwrap(),
+        Rgba::new(1.0, 0.533, 0.0, 0.502),
+    );
+    assert!(parse("#ff88").is_ok());
+    assert!(parse("#ff8").is_ok());
+    assert!(parse("#ff88000").is_err());
+    assert!(parse("#gg0000").is_err());
+}
+
+#[test]
+fn parse_css() {
+    assert_close(
+        parse("rgb(255, 128, 0)").unwrap(),
+        Rgb::new(1.0, 0.502, 0.0),
+    );
+    assert_close(
+        parse("rgba(100% 0% 0% / 50%)").unwrap(),
+        Rgba::new(1.0, 0.0, 0.0, 0.5),
+    );
+    assert_close(parse("hsl(120deg, 100%, 50%)").unwrap(), GREEN);
+    assert_close(
+        parse("hsla(0.5turn 100% 50% / 0.25)").unwrap(),
+        Rgba::new(0.0, 1.0, 1.0, 0.25),
+    );
+    assert_close(parse("oklch(100% 0 0)").unwrap(), WHITE);
+    assert_close(
+        parse(" transparent ").unwrap(),
+        Rgba::new(0.0, 0.0, 0.0, 0.0),
+    );
+    assert_close(parse("RebeccaPurple").unwrap(), hex(0x663399));
+    assert!(parse("rgb(1, 2)").is_err());
+    assert!(parse("lab(1 2 3)").is_err());
+}
+
+#[test]
+fn conversions() {
+    let color = hex(0x3366cc);
+    assert_close(color.to_hsv(), color);
+    assert_close(color.to_hsl(), color);
+    assert_close(color.to_oklab(), color);
+    assert_close(color.to_oklch(), color);
+    assert_close(Hsv::new(30.0, 1.0, 1.0), Rgb::new(1.0, 0.5, 0.0));
+    assert_close(Hsl::new(240.0, 1.0, 0.25), Rgb::new(0.0, 0.0, 0.5));
+    let lab = WHITE.to_oklab();
+    assert!((lab.l - 1.0).abs() < 1e-3 && lab.a.abs() < 1e-3 && lab.b.abs() < 1e-3);
+}
+
+#[test]
+fn perceptual_mix() {
+    assert_close(RED.mix_oklab(BLUE, 0.0), RED);
+    assert_close(RED.mix_oklab(BLUE, 1.0), BLUE);
+    let gray = BLACK.mix_oklab(WHITE, 0.5);
+    assert!((gray.to_oklab().l - 0.5).abs() < 1e-3);
+    let hue = Oklch::new(0.7, 0.1, 350.0)
+        .mix(Oklch::new(0.7, 0.1, 30.0), 0.5)
+        .h;
+    assert!((hue - 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn color_spaces() {
+    let color = hex(0x808080);
+    assert_eq!(color.to_color_space(ColorSpace::Srgb), color.to_rgba());
+    assert_close(
+        color.to_color_space(ColorSpace::Linear),
+        Rgb::new(0.216, 0.216, 0.216),
+    );
+    assert_close(color.srgb_to_linear().linear_to_srgb(), color);
+    for constant in [BLACK, RED, YELLOW, GREEN, CYAN, BLUE, MAGENTA, WHITE] {
+        assert_eq!(
+            constant.to_color_space(ColorSpace::Linear),
+            constant.to_rgba()
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{ClipRect, Graphics, Profiler, Target};
+
+/// Target that renders into a rectangle of the underlying target.
+///
+/// Cameras of the viewport are computed from the size of the rectangle,
+/// so multiple scenes can be rendered side by side into a single frame.
+///
+/// Clearing affects the whole underlying target, not only the viewport.
+pub struct Viewport<'a, T: Target + ?Sized> {
+    target: &'a mut T,
+    /// Rectangle in the coordinates of the underlying view.
+    rect: ClipRect,
+}
+
+impl<'a, T: Target + ?Sized> Viewport<'a, T> {
+    /// The `rect` is relative to the `target` and clamped to its size.
+    pub fn new(target: &'a mut T, rect: ClipRect) -> Self {
+        let rect = rect.clamp(target.size());
+        let rect = match target.viewport_rect() {
+            Some(parent) => rect.offset((parent.x, parent.y)),
+            None => rect,
+        };
+        Self { target, rect }
+    }
+
+    pub fn rect(&self) -> ClipRect {
+        self.rect
+    }
+}
+
+impl<T: Target + ?Sized> Target for Viewport<'_, T> {
+    fn state(&self) -> &Graphics {
+        self.target.state()
+    }
+    fn profiler(&self) -> &Profiler {
+        self.target.profiler()
+    }
+    fn view(&self) -> &wgpu::TextureView {
+        self.target.view()
+    }
+    fn multisample_view(&self) -> Option<&wgpu::TextureView> {
+        self.target.multisample_view()
+    }
+    fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.target.depth_view()
+    }
+    fn viewport_rect(&self) -> Option<ClipRect> {
+        Some(self.rect)
+    }
+    fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        self.target.encoder()
+    }
+    fn pending_clear(&mut self) -> &mut Option<wgpu::Color> {
+        self.target.pending_clear()
+    }
+    fn pending_depth_clear(&mut self) -> &mut bool {
+        self.target.pending_depth_clear()
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.rect.width, self.rect.height)
+    }
+}
@@ -34,6 +34,11 @@ pub trait Attribute: 'static {
         self.store(&mut dst);
         dst.data
     }
+
+    /// Converts value to bytes laid out as a WGSL uniform struct with fields from [`Self::bindings`].
+    fn to_uniform_bytes(&self) -> Vec<u8> {
+        Self::bindings().to_uniform(&self.to_bytes())
+    }
 }
 
 impl<T: 'static> Attribute for PhantomData<T> {
@@ -72,6 +72,81 @@ impl BindingList {
         self.len() as u32
     }
 
+    /// Offsets of bindings as fields of a WGSL uniform struct and the size of the struct.
+    pub fn uniform_layout(&self) -> (Vec<u64>, u64) {
+        let mut offsets = Vec::with_capacity(self.len());
+        let mut size = 0u64;
+        for Binding { ty, .. } in self {
+            let offset = size.next_multiple_of(ty.uniform_align());
+            offsets.push(offset);
+            size = offset + ty.uniform_size();
+        }
+        // Uniform structs are aligned to 16 bytes.
+        (offsets, size.next_multiple_of(16))
+    }
+
+    /// Lays out packed attribute data as a WGSL uniform struct.
+    pub fn to_uniform(&self, packed: &[u8]) -> Vec<u8> {
+        let (offsets, size) = self.uniform_layout();
+        let mut data = vec![0; size as usize];
+        let mut src = 0;
+        for (Binding { ty, .. }, offset) in self.iter().zip(offsets) {
+            let end = (src + ty.size() as usize).min(packed.len());
+            ty.copy_to_uniform(&packed[src..end], &mut data[offset as usize..]);
+            src = end;
+        }
+        data
+    }
+
+    /// WGSL declarations for storage buffer items that are laid out as packed attributes.
+    ///
+    /// WGSL aligns vectors, so packed data is declared as `{name}Packed` struct of scalar arrays.
+    /// Struct `{name}` with vector fields is converted from and to it using `pack_{name}` and `unpack_{name}` functions.
+    /// Only 32-bit scalars and vectors are supported.
+    pub fn to_wgsl_storage(&self, name: &str) -> Result<String> {
+        let mut fields = String::new();
+        let mut packed_fields = String::new();
+        let mut pack = String::new();
+        let mut unpack = String::new();
+        for Binding { name: field, ty } in self {
+            if ty.item.size() != 4 {
+                bail!(
+                    "Only 32-bit scalars are supported in storage, got {}",
+                    ty.item
+                );
+            }
+            let field = if field.is_empty() { "value" } else { field };
+            let wgsl = ty.to_wgsl()?;
+            fields += &format!("    {field}: {wgsl},\n");
+            match ty.dims.as_slice() {
+                [] => {
+                    packed_fields += &format!("    {field}: {wgsl},\n");
+                    pack += &format!("    packed.{field} = value.{field};\n");
+                    unpack += &format!("    value.{field} = packed.{field};\n");
+                }
+                [n] => {
+                    let components = |src: &str| {
+                        (0..*n)
+                            .map(|i| format!("{src}.{field}[{i}]"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let array = format!("array<{}, {n}>", ty.item);
+                    packed_fields += &format!("    {field}: {array},\n");
+                    pack += &format!("    packed.{field} = {array}({});\n", components("value"));
+                    unpack += &format!("    value.{field} = {wgsl}({});\n", components("packed"));
+                }
+                _ => bail!("Matrices are not supported in storage, got {wgsl}"),
+            }
+        }
+        Ok(format!(
+            "struct {name} {{\n{fields}}};\n\n\
+             struct {name}Packed {{\n{packed_fields}}};\n\n\
+             fn pack_{name}(value: {name}) -> {name}Packed {{\n    var packed: {name}Packed;\n{pack}    return packed;\n}}\n\n\
+             fn unpack_{name}(packed: {name}Packed) -> {name} {{\n    var value: {name};\n{unpack}    return value;\n}}\n"
+        ))
+    }
+
     pub fn layout(&self, start_location: u32) -> Result<Vec<wgpu::VertexAttribute>> {
         self.0
             .iter()
@@ -198,6 +273,43 @@ impl BindingType {
     pub fn size(&self) -> u64 {
         self.item.size() * self.dims.iter().product::<usize>() as u64
     }
+
+    /// Number of columns and size of a single column, matrices are stored column by column.
+    fn columns(&self) -> (u64, u64) {
+        match self.dims.as_slice() {
+            [m, n] => (*m as u64, self.item.size() * *n as u64),
+            _ => (1, self.size()),
+        }
+    }
+
+    /// Alignment of the type in a uniform buffer according to WGSL rules.
+    pub fn uniform_align(&self) -> u64 {
+        let item = self.item.size();
+        match self.dims.as_slice() {
+            [] => item,
+            [2] | [_, 2] => 2 * item,
+            _ => 4 * item,
+        }
+    }
+
+    /// Size of the type in a uniform buffer, including padding between matrix columns.
+    pub fn uniform_size(&self) -> u64 {
+        let (count, size) = self.columns();
+        if count > 1 {
+            count * size.next_multiple_of(self.uniform_align())
+        } else {
+            size
+        }
+    }
+
+    /// Copies packed `src` into `dst` laid out as in a uniform buffer.
+    fn copy_to_uniform(&self, src: &[u8], dst: &mut [u8]) {
+        let (count, size) = self.columns();
+        let stride = size.next_multiple_of(self.uniform_align()) as usize;
+        for (i, column) in src.chunks(size as usize).take(count as usize).enumerate() {
+            dst[i * stride..][..column.len()].copy_from_slice(column);
+        }
+    }
 }
 
 impl Serialize for BindingType {
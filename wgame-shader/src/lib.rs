@@ -7,6 +7,8 @@
 mod attribute;
 mod binding;
 mod shader;
+#[cfg(test)]
+mod tests;
 
 pub use self::{
     attribute::{Attribute, BytesSink},
@@ -0,0 +1,109 @@
+use crate::{Binding, BindingList, BindingType, binding_type};
+
+fn bindings(types: impl IntoIterator<Item = (&'static str, BindingType)>) -> BindingList {
+    types
+        .into_iter()
+        .map(|(name, ty)| Binding::new(name, ty))
+        .collect()
+}
+
+fn floats(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks(4)
+        .map(|c| f32::from_ne_bytes(c.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn uniform_layout_vectors() {
+    let list = bindings([
+        ("a", binding_type!(F32)),
+        ("b", binding_type!(F32, 3)),
+        ("c", binding_type!(F32)),
+        ("d", binding_type!(F32, 2)),
+    ]);
+    // Scalar fits into the padding after `vec3`.
+    assert_eq!(list.uniform_layout(), (vec![0, 16, 28, 32], 48));
+}
+
+#[test]
+fn uniform_layout_matrices() {
+    let list = bindings([
+        ("a", binding_type!(F32)),
+        ("m", binding_type!(F32, 3, 3)),
+        ("n", binding_type!(F32, 4, 2)),
+        ("v", binding_type!(F32, 3)),
+        ("w", binding_type!(F32)),
+    ]);
+    // `mat3x3` columns are padded to 16 bytes, `mat4x2` ones are 8-byte aligned without padding.
+    assert_eq!(list.uniform_layout(), (vec![0, 16, 64, 96, 108], 112));
+}
+
+#[test]
+fn to_uniform() {
+    let list = bindings([
+        ("a", binding_type!(F32)),
+        ("m", binding_type!(F32, 3, 3)),
+        ("n", binding_type!(F32, 4, 2)),
+    ]);
+    let packed: Vec<u8> = (1..=18).flat_map(|x| (x as f32).to_ne_bytes()).collect();
+    assert_eq!(packed.len() as u64, list.size());
+    assert_eq!(
+        floats(&list.to_uniform(&packed)),
+        [
+            1.0, 0.0, 0.0, 0.0, //
+            2.0, 3.0, 4.0, 0.0, //
+            5.0, 6.0, 7.0, 0.0, //
+            8.0, 9.0, 10.0, 0.0, //
+            11.0, 12.0, 13.0, 14.0, //
+            15.0, 16.0, 17.0, 18.0, //
+        ]
+    );
+}
+
+#[test]
+fn to_wgsl_storage() {
+    let list = bindings([("pos", binding_type!(F32, 3)), ("", binding_type!(U32))]);
+    assert_eq!(
+        list.to_wgsl_storage("Item").unwrap(),
+        "\
+struct Item {
+    pos: vec3<f32>,
+    value: u32,
+};
+
+struct ItemPacked {
+    pos: array<f32, 3>,
+    value: u32,
+};
+
+fn pack_Item(value: Item) -> ItemPacked {
+    var packed: ItemPacked;
+    packed.pos = array<f32, 3>(value.pos[0], value.pos[1], value.pos[2]);
+    packed.value = value.value;
+    return packed;
+}
+
+fn unpack_Item(packed: ItemPacked) -> Item {
+    var value: Item;
+    value.pos = vec3<f32>(packed.pos[0], packed.pos[1], packed.pos[2]);
+    value.value = packed.value;
+    return value;
+}
+"
+    );
+}
+
+#[test]
+fn to_wgsl_storage_unsupported() {
+    assert!(
+        bindings([("m", binding_type!(F32, 4, 4))])
+            .to_wgsl_storage("Item")
+            .is_err()
+    );
+    assert!(
+        bindings([("h", binding_type!(F16, 2))])
+            .to_wgsl_storage("Item")
+            .is_err()
+    );
+}
@@ -2,7 +2,7 @@
 
 use crate::{
     app::{Size, WindowAttributes},
-    gfx::{self, PresentMode},
+    gfx::{self, ColorSpace, HdrConfig, PresentMode, TextureFormat},
 };
 
 /// Configuration for a window.
@@ -14,6 +14,8 @@ pub struct WindowConfig {
     pub app: WindowAttributes,
     /// Graphics configuration.
     pub gfx: gfx::Config,
+    /// Graphics state of another window to render with, `gfx` is ignored if set.
+    pub graphics: Option<gfx::Graphics>,
 }
 
 impl WindowConfig {
@@ -50,6 +52,72 @@ impl WindowConfig {
                 } else {
                     PresentMode::AutoNoVsync
                 },
+                ..self.gfx
+            },
+            ..self
+        }
+    }
+
+    /// Sets the number of samples per pixel for multisample anti-aliasing.
+    pub fn msaa(self, samples: u32) -> Self {
+        Self {
+            gfx: gfx::Config {
+                msaa_samples: samples,
+                ..self.gfx
+            },
+            ..self
+        }
+    }
+
+    /// Sets whether render targets have depth and stencil buffer.
+    pub fn depth_buffer(self, enabled: bool) -> Self {
+        Self {
+            gfx: gfx::Config {
+                depth_format: enabled.then_some(TextureFormat::Depth24PlusStencil8),
+                ..self.gfx
+            },
+            ..self
+        }
+    }
+
+    /// Sets the color space in which rendering is performed.
+    pub fn color_space(self, color_space: ColorSpace) -> Self {
+        Self {
+            gfx: gfx::Config {
+                color_space,
+                ..self.gfx
+            },
+            ..self
+        }
+    }
+
+    /// Sets whether the window is rendered in HDR with default tonemapping.
+    pub fn hdr(self, enabled: bool) -> Self {
+        Self {
+            gfx: gfx::Config {
+                hdr: enabled.then(HdrConfig::default),
+                ..self.gfx
+            },
+            ..self
+        }
+    }
+
+    /// Renders the window with the graphics state of another window.
+    ///
+    /// Textures and fonts of a single [`Library`](crate::Library) can be used in both windows then.
+    pub fn share_graphics(self, state: &gfx::Graphics) -> Self {
+        Self {
+            graphics: Some(state.clone()),
+            ..self
+        }
+    }
+
+    /// Sets whether render passes are timed on GPU, see [`gfx::FrameStats`].
+    pub fn profiling(self, enabled: bool) -> Self {
+        Self {
+            gfx: gfx::Config {
+                profiling: enabled,
+                ..self.gfx
             },
             ..self
         }
@@ -1,3 +1,8 @@
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+};
+
 use anyhow::Result;
 use half::f16;
 use rgb::Rgba;
@@ -14,8 +19,17 @@ use crate::{
     texture::{Texture, TexturingLibrary},
 };
 
+/// Libraries of graphics resources, clones of it share the resources.
+///
+/// When the device is lost and the graphics state is replaced (see [`Graphics::replacement`]),
+/// the resources are recreated on the new state the next time the library is used.
+/// Textures remain valid, but shapes and fonts must be created again from the library.
 #[derive(Clone)]
 pub struct Library {
+    inner: Rc<RefCell<LibraryInner>>,
+}
+
+struct LibraryInner {
     state: Graphics,
     texturing: TexturingLibrary,
     #[cfg(feature = "shapes")]
@@ -29,48 +43,75 @@ impl Library {
         let state = state.clone();
         let texture = TexturingLibrary::new(&state);
         Self {
-            #[cfg(feature = "shapes")]
-            shapes: ShapesLibrary::new(&state, &texture),
-            #[cfg(feature = "typography")]
-            typography: TypographyLibrary::new(&texture),
-            texturing: texture,
-            state,
+            inner: Rc::new(RefCell::new(LibraryInner {
+                #[cfg(feature = "shapes")]
+                shapes: ShapesLibrary::new(&state, &texture),
+                #[cfg(feature = "typography")]
+                typography: TypographyLibrary::new(&texture),
+                texturing: texture,
+                state,
+            })),
         }
     }
 
-    pub fn state(&self) -> &Graphics {
-        &self.state
+    /// Returns resources, they are recreated first if the graphics state has been replaced.
+    fn inner(&self) -> Ref<'_, LibraryInner> {
+        let replacement = self.inner.borrow().state.replacement();
+        if let Some(state) = replacement {
+            self.inner.borrow_mut().recreate(&state);
+        }
+        self.inner.borrow()
     }
 
-    pub fn texturing(&self) -> &TexturingLibrary {
-        &self.texturing
+    pub fn state(&self) -> Graphics {
+        self.inner().state.clone()
+    }
+
+    pub fn texturing(&self) -> TexturingLibrary {
+        self.inner().texturing.clone()
     }
     #[cfg(feature = "shapes")]
-    pub fn shapes(&self) -> &ShapesLibrary {
-        &self.shapes
+    pub fn shapes(&self) -> ShapesLibrary {
+        self.inner().shapes.clone()
     }
     #[cfg(feature = "typography")]
-    pub fn typography(&self) -> &TypographyLibrary {
-        &self.typography
+    pub fn typography(&self) -> TypographyLibrary {
+        self.inner().typography.clone()
     }
 
     pub fn make_texture(&self, image: &Image<Rgba<f16>>, settings: TextureSettings) -> Texture {
-        self.texturing.texture(image, settings)
+        self.inner().texturing.texture(image, settings)
     }
     pub async fn load_texture(
         &self,
         path: impl AsRef<Path>,
         settings: TextureSettings,
     ) -> Result<Texture> {
-        Ok(self.make_texture(&Image::decode_auto(&read_bytes(path).await?)?, settings))
+        let image = Image::decode_auto(&read_bytes(path).await?)?;
+        Ok(self.inner().texturing.texture_srgb(&image, settings))
     }
 
     #[cfg(feature = "typography")]
     pub fn make_font(&self, font: &FontData) -> Font {
-        Font::new(&self.typography, font)
+        Font::new(&self.inner().typography, font)
     }
     #[cfg(feature = "typography")]
     pub async fn load_font(&self, path: impl AsRef<Path>) -> Result<Font> {
         Ok(self.make_font(&FontData::new(read_bytes(path).await?, 0)?))
     }
 }
+
+impl LibraryInner {
+    /// Recreates GPU resources on the new graphics state after device loss.
+    fn recreate(&mut self, state: &Graphics) {
+        log::info!("Recreating library resources");
+        self.state = state.clone();
+        self.texturing.recreate(state);
+        #[cfg(feature = "shapes")]
+        {
+            self.shapes = ShapesLibrary::new(state, &self.texturing);
+        }
+        #[cfg(feature = "typography")]
+        self.typography.recreate(&self.texturing);
+    }
+}
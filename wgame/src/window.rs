@@ -20,7 +20,7 @@ where
     F: AsyncFnOnce(Window<'_>) -> T + 'static,
 {
     app::create_windowed_task(rt, config.app, async move |app_window| {
-        let window = Window::new(app_window, config.gfx).await?;
+        let window = Window::new(app_window, config.gfx, config.graphics).await?;
         Ok(window_fn(window).await)
     })
 }
@@ -39,9 +39,16 @@ pub struct Window<'a> {
 }
 
 impl<'a> Window<'a> {
-    async fn new(app: app::Window<'a>, gfx_cfg: gfx::Config) -> Result<Self> {
-        let mut gfx = gfx::Surface::new(gfx_cfg, app.raw()).await?;
-        gfx.resize(app.size());
+    async fn new(
+        app: app::Window<'a>,
+        gfx_cfg: gfx::Config,
+        shared: Option<gfx::Graphics>,
+    ) -> Result<Self> {
+        let mut gfx = match shared {
+            Some(state) => gfx::Surface::with_graphics(&state, app.raw())?,
+            None => gfx::Surface::new(gfx_cfg, app.raw()).await?,
+        };
+        gfx.resize(app.size())?;
         Ok(Self { app, gfx })
     }
 
@@ -49,24 +56,34 @@ impl<'a> Window<'a> {
         self.app.input()
     }
 
+    /// Waits for the next frame, skipped frames are not returned.
     pub async fn next_frame(&mut self) -> Result<Option<Frame<'a, '_>>> {
-        match self.app.request_redraw().await {
-            None => Ok(None),
-            Some(redraw) => {
-                if let Some(size) = redraw.resized() {
-                    self.gfx.resize(size);
-                }
-                Ok(Some(Frame {
-                    app: redraw,
-                    gfx: Some(self.gfx.frame()?),
-                }))
+        loop {
+            let Some(redraw) = self.app.request_redraw().await else {
+                return Ok(None);
+            };
+            if let Some(size) = redraw.resized() {
+                self.gfx.resize(size)?;
             }
+            if !self.gfx.acquire().await? {
+                continue;
+            }
+            let gfx = self.gfx.frame().await?;
+            return Ok(gfx.map(|gfx| Frame {
+                app: redraw,
+                gfx: Some(gfx),
+            }));
         }
     }
 
     pub fn graphics(&self) -> &gfx::Graphics {
         self.gfx.state()
     }
+
+    /// Statistics of the last presented frame.
+    pub fn last_frame_stats(&self) -> gfx::FrameStats {
+        self.gfx.profiler().last_frame()
+    }
 }
 
 pub struct Frame<'a, 'b> {
@@ -89,12 +106,22 @@ impl DerefMut for Frame<'_, '_> {
 
 impl Drop for Frame<'_, '_> {
     fn drop(&mut self) {
-        self.app.pre_present();
-        self.gfx.take().unwrap().present();
+        if let Some(gfx) = self.gfx.take() {
+            self.app.pre_present();
+            gfx.present();
+        }
     }
 }
 
 impl Frame<'_, '_> {
+    /// Presents the frame and returns its statistics.
+    ///
+    /// Frame is presented on drop as well, then the statistics are available from [`Window::last_frame_stats`].
+    pub fn present(mut self) -> gfx::FrameStats {
+        self.app.pre_present();
+        self.gfx.take().unwrap().present()
+    }
+
     pub fn size(&self) -> (u32, u32) {
         self.app.size()
     }